@@ -0,0 +1,125 @@
+//! Pretends to be the serial controller so the game can be played and tested without
+//! hardware. Prints the port to start the game with, then sends the lines of a script
+//! (or of stdin) and prints whatever the game sends back.
+//!
+//! Besides protocol lines such as `JUMP`, a script may contain:
+//!   `wait <ms>`      pause before the next line
+//!   `expect <LINE>`  wait up to 5 s for the game to send LINE, failing otherwise
+//!   `# ...`          comments and blank lines are skipped
+
+use std::process::ExitCode;
+
+#[cfg(unix)]
+fn main() -> ExitCode {
+    use std::io::BufRead;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use clap::Parser;
+    use rust_spil::serial::sim::VirtualController;
+
+    #[derive(Parser)]
+    #[command(about = "Virtual serial controller for rust_spil")]
+    struct Args {
+        /// Script of lines to send; reads stdin interactively if omitted
+        script: Option<PathBuf>,
+
+        /// Device ID sent in the HELLO handshake
+        #[arg(long, default_value = "controller_sim")]
+        device_id: String,
+
+        /// Skip the HELLO handshake, like old firmware
+        #[arg(long)]
+        no_hello: bool,
+
+        /// Don't answer the game's PING, to test the silence timeout
+        #[arg(long)]
+        no_pong: bool,
+    }
+
+    let args = Args::parse();
+
+    let controller = match VirtualController::new() {
+        Ok(controller) => Arc::new(controller),
+        Err(err) => {
+            eprintln!("error: could not create a pseudo-terminal: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    controller.set_auto_pong(!args.no_pong);
+    eprintln!(
+        "Virtual controller on {0}\nStart the game with: rust_spil --serial-port {0} --baud 0",
+        controller.port_name()
+    );
+
+    let printer = controller.clone();
+    thread::spawn(move || {
+        let mut printed = 0;
+        loop {
+            let lines = printer.received();
+            for line in &lines[printed..] {
+                println!("<< {line}");
+            }
+            printed = lines.len();
+            thread::sleep(Duration::from_millis(20));
+        }
+    });
+
+    let input: Box<dyn BufRead> = match &args.script {
+        Some(path) => match std::fs::File::open(path) {
+            Ok(file) => Box::new(std::io::BufReader::new(file)),
+            Err(err) => {
+                eprintln!("error: could not open {}: {err}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(std::io::stdin().lock()),
+    };
+
+    // The game announces its state as soon as it opens the port; greet it then
+    if !args.no_hello {
+        if !controller.wait_for(|_| true, Duration::from_secs(30)) {
+            eprintln!("warning: the game hasn't opened the port yet, sending HELLO anyway");
+        }
+        if let Err(err) = controller.hello(&args.device_id) {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    for line in input.lines() {
+        let Ok(line) = line else { break };
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else if let Some(ms) = line.strip_prefix("wait ") {
+            match ms.trim().parse() {
+                Ok(ms) => thread::sleep(Duration::from_millis(ms)),
+                Err(_) => eprintln!("warning: bad wait {ms:?}"),
+            }
+        } else if let Some(expected) = line.strip_prefix("expect ") {
+            if !controller.wait_for_line(expected.trim(), Duration::from_secs(5)) {
+                eprintln!("error: the game never sent {:?}", expected.trim());
+                return ExitCode::FAILURE;
+            }
+        } else if let Err(err) = controller.send(line) {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        } else {
+            println!(">> {line}");
+        }
+    }
+
+    // Let the last replies arrive before the port goes away
+    thread::sleep(Duration::from_millis(200));
+    ExitCode::SUCCESS
+}
+
+#[cfg(not(unix))]
+fn main() -> ExitCode {
+    eprintln!("controller_sim needs pseudo-terminals, which this platform doesn't have");
+    ExitCode::FAILURE
+}
@@ -0,0 +1,206 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use bevy_egui::EguiPlugin;
+use clap::Parser;
+
+use crate::config::{ConfigPlugin, GameConfig};
+use crate::hitbox::ShowHitboxes;
+use crate::headless::{exit_on_game_over, headless_app, start_run};
+use crate::replay::{PlaybackPlugin, RecordPlugin, Recording};
+use crate::resources::{GameState, RngSeed};
+use crate::serial::SerialSettings;
+use crate::GamePlugins;
+
+/// Command-line options for launching the game in a specific configuration.
+#[derive(Parser, Debug)]
+#[command(name = "rust_spil", about = "Side-scrolling spike dodger", version)]
+pub struct Cli {
+    /// Seed for spike and coin placement; random per run if omitted
+    #[arg(long, conflicts_with = "replay")]
+    pub seed: Option<u64>,
+
+    /// Serial port of the jump controller, e.g. COM5 or /dev/ttyUSB0; scanned for if omitted
+    #[arg(long, value_name = "PORT", conflicts_with = "headless")]
+    pub serial_port: Option<String>,
+
+    /// Only pick up USB controllers with this vendor ID (hex, e.g. 2341)
+    #[arg(
+        long,
+        value_name = "VID",
+        value_parser = parse_hex_u16,
+        conflicts_with_all = ["serial_port", "headless"]
+    )]
+    pub serial_vid: Option<u16>,
+
+    /// Only pick up USB controllers with this product ID (hex, e.g. 0043)
+    #[arg(
+        long,
+        value_name = "PID",
+        value_parser = parse_hex_u16,
+        conflicts_with_all = ["serial_port", "headless"]
+    )]
+    pub serial_pid: Option<u16>,
+
+    /// Baud rate of the jump controller; 0 for pseudo-terminals such as controller_sim
+    #[arg(long, value_parser = parse_baud, conflicts_with = "headless")]
+    pub baud: Option<u32>,
+
+    /// Most score updates sent to the controller per second
+    #[arg(long, value_name = "HZ", value_parser = parse_score_rate, conflicts_with = "headless")]
+    pub score_rate: Option<f32>,
+
+    /// Simulate without a window or renderer and exit when the run ends
+    #[arg(long, conflicts_with = "fullscreen")]
+    pub headless: bool,
+
+    /// Play back a recorded run instead of live input
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["record", "config"])]
+    pub replay: Option<PathBuf>,
+
+    /// Record each run's inputs to this file
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Start in borderless fullscreen
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Go straight into a run without the main menu
+    #[arg(long, conflicts_with = "headless")]
+    pub skip_menu: bool,
+
+    /// Draw collision hitboxes from the start; F3 toggles them in game
+    #[arg(long, conflicts_with = "headless")]
+    pub show_hitboxes: bool,
+
+    /// Tuning file to load instead of assets/game.tuning.ron; its name must end in
+    /// .tuning.ron. Edits to it are only picked up between runs if it is inside assets/
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Builds the app these options describe, or explains why they can't work.
+    pub fn build_app(&self) -> Result<App, String> {
+        let recording = self.replay.as_deref().map(Recording::load).transpose()?;
+        let tuning = self.config.as_deref().map(load_tuning).transpose()?;
+
+        let mut app = if self.headless {
+            let tick = recording
+                .as_ref()
+                .map_or(Duration::from_secs_f64(1.0 / 60.0), |recording| recording.timestep);
+            let mut app = headless_app(tick);
+            if let Some((_, config)) = &tuning {
+                app.insert_resource(config.clone());
+            }
+            app.add_systems(OnEnter(GameState::GameOver), exit_on_game_over);
+            app
+        } else {
+            let mut app = App::new();
+            let mode = if self.fullscreen {
+                WindowMode::BorderlessFullscreen
+            } else {
+                WindowMode::Windowed
+            };
+            app.add_plugins(
+                DefaultPlugins
+                    .set(ImagePlugin::default_nearest())
+                    .set(WindowPlugin {
+                        primary_window: Some(Window { mode, ..default() }),
+                        ..default()
+                    }),
+            )
+            .add_plugins(EguiPlugin);
+
+            let mut game = GamePlugins.build();
+            if let Some((path, _)) = &tuning {
+                game = game.set(ConfigPlugin { path: tuning_asset_path(path) });
+            }
+            if recording.is_some() {
+                // A replay must run with the tuning it was recorded with
+                game = game.disable::<ConfigPlugin>();
+            }
+            app.add_plugins(game);
+            // The asset loads in the background; start out with the checked values meanwhile
+            if let Some((_, config)) = &tuning {
+                app.insert_resource(config.clone());
+            }
+
+            if self.skip_menu || recording.is_some() {
+                app.add_systems(Startup, start_run);
+            }
+            if self.show_hitboxes {
+                app.insert_resource(ShowHitboxes(true));
+            }
+            app
+        };
+
+        if self.seed.is_some() {
+            app.insert_resource(RngSeed(self.seed));
+        }
+        let defaults = SerialSettings::default();
+        app.insert_resource(SerialSettings {
+            port: self.serial_port.clone(),
+            baud: self.baud.unwrap_or(defaults.baud),
+            vid: self.serial_vid,
+            pid: self.serial_pid,
+            score_rate: self.score_rate.unwrap_or(defaults.score_rate),
+        });
+        if let Some(recording) = recording {
+            app.add_plugins(PlaybackPlugin { recording });
+        }
+        if let Some(path) = &self.record {
+            app.add_plugins(RecordPlugin { path: path.clone() });
+        }
+
+        Ok(app)
+    }
+}
+
+/// Checks a `--config` file before anything starts, so a bad one stops the game instead
+/// of leaving it on the default tuning.
+fn load_tuning(path: &Path) -> Result<(PathBuf, GameConfig), String> {
+    let path = fs::canonicalize(path).map_err(|err| format!("--config {}: {err}", path.display()))?;
+    // The asset server picks its loader by this extension
+    if !path.to_string_lossy().ends_with(".tuning.ron") {
+        return Err(format!("--config {}: file name must end in .tuning.ron", path.display()));
+    }
+    let config = GameConfig::load(&path)?;
+    Ok((path, config))
+}
+
+/// The asset path for a tuning file. One inside the assets folder is given relative to
+/// it, where the file watcher sees it change; anything else is loaded by its full path.
+fn tuning_asset_path(path: &Path) -> String {
+    let assets = fs::canonicalize(FileAssetReader::get_base_path().join("assets"));
+    let relative = assets.ok().and_then(|assets| path.strip_prefix(assets).ok().map(Path::to_path_buf));
+    relative.as_deref().unwrap_or(path).to_string_lossy().into_owned()
+}
+
+fn parse_hex_u16(value: &str) -> Result<u16, String> {
+    let digits = value.trim_start_matches("0x");
+    u16::from_str_radix(digits, 16).map_err(|err| format!("expected a hex USB ID: {err}"))
+}
+
+fn parse_score_rate(value: &str) -> Result<f32, String> {
+    let rate: f32 = value.parse().map_err(|err| format!("expected a number: {err}"))?;
+    if rate > 0.0 && rate.is_finite() {
+        Ok(rate)
+    } else {
+        Err("must be greater than zero".to_string())
+    }
+}
+
+fn parse_baud(value: &str) -> Result<u32, String> {
+    let baud: u32 = value.parse().map_err(|err| format!("expected a number: {err}"))?;
+    if baud == 0 || (300..=4_000_000).contains(&baud) {
+        Ok(baud)
+    } else {
+        Err("must be 0 or between 300 and 4000000".to_string())
+    }
+}
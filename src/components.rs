@@ -1,10 +1,19 @@
 use bevy::prelude::*;
 
+use crate::resources::GameState;
+
 #[derive(Component)]
 pub struct Player {
     pub velocity: f32,
+    /// Resting on the ground, or in flip mode on whichever surface it is flipped to.
     pub on_ground: bool,
     pub flipped: bool,
+    /// Seconds a jump or flip press stays pending while it can't be used yet.
+    pub jump_buffer: f32,
+    /// Seconds left in which a jump is still allowed after leaving the ground.
+    pub coyote_time: f32,
+    /// Jump held since it was last pressed; a rising jump is cut short once it isn't.
+    pub jump_held: bool,
 }
 
 #[derive(Component)]
@@ -13,5 +22,24 @@ pub struct Obstacle;
 #[derive(Component)]
 pub struct Coin;
 
+/// Sends `PlayerHitHazard` when the player's hitbox touches this entity's.
+#[derive(Component)]
+pub struct Hazard;
+
+/// Sends `PlayerTouchedPickup` when the player's hitbox touches this entity's.
+#[derive(Component)]
+pub struct Pickup;
+
+#[derive(Component)]
+pub struct MainCamera;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+/// Despawned (with its children) when the game leaves this state.
+#[derive(Component)]
+pub struct DespawnOnExit(pub GameState);
+
+/// Part of the world of a single run. Despawned when the next run starts.
 #[derive(Component)]
-pub struct MainCamera; // ✅ Used to track and despawn the camera on restart
+pub struct RunScoped;
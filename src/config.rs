@@ -0,0 +1,216 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::ActiveRun;
+
+/// Every gameplay tuning value. Loaded from `assets/game.tuning.ron` and
+/// re-applied whenever that file changes, so the feel can be tuned between runs.
+#[derive(Resource, Asset, TypePath, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GameConfig {
+    pub gravity: f32,
+    pub jump_velocity: f32,
+    pub obstacle_speed: f32,
+    pub min_spawn_time: f32,
+    pub max_spawn_time: f32,
+    pub ground_y: f32,
+    pub ceiling_y: f32,
+    /// Score at which the ceiling-flip mode, ceiling spikes and coins kick in.
+    pub flip_score_threshold: f32,
+    /// Vertical speed of the ground/ceiling flip, in px/s.
+    pub flip_speed: f32,
+    /// How long before landing a jump press is remembered and still jumps, in s.
+    pub jump_buffer_time: f32,
+    /// How long after leaving the ground a jump is still allowed, in s.
+    pub coyote_time: f32,
+    /// Upward speed a jump is cut to once the button is let go, as a fraction of
+    /// `jump_velocity`. 1.0 makes every jump full height.
+    pub jump_release_cut: f32,
+    /// How long before reaching a surface a flip press is remembered, in s.
+    pub flip_buffer_time: f32,
+    /// Size of a coin's pickup hitbox, a square around its centre.
+    pub coin_hitbox_size: f32,
+    /// Obstacles and coins past this x are despawned.
+    pub despawn_x: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            gravity: -600.0,
+            jump_velocity: 300.0,
+            obstacle_speed: -200.0,
+            min_spawn_time: 1.0,
+            max_spawn_time: 3.0,
+            ground_y: -240.0,
+            ceiling_y: 240.0,
+            flip_score_threshold: 100.0,
+            flip_speed: 500.0,
+            jump_buffer_time: 0.12,
+            coyote_time: 0.08,
+            jump_release_cut: 0.4,
+            flip_buffer_time: 0.12,
+            coin_hitbox_size: 30.0,
+            despawn_x: -400.0,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Reads a tuning file directly, for apps without an asset server.
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let config: Self = crate::ron_file::load_ron(path, "config")?;
+        config
+            .validate()
+            .map_err(|err| format!("invalid config {}: {err}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Checks for values the game can't run with, e.g. a spawn range that is
+    /// empty or a NaN anywhere, and lists every problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+
+        let values = [
+            self.gravity,
+            self.jump_velocity,
+            self.obstacle_speed,
+            self.min_spawn_time,
+            self.max_spawn_time,
+            self.ground_y,
+            self.ceiling_y,
+            self.flip_score_threshold,
+            self.flip_speed,
+            self.jump_buffer_time,
+            self.coyote_time,
+            self.jump_release_cut,
+            self.flip_buffer_time,
+            self.coin_hitbox_size,
+            self.despawn_x,
+        ];
+        check(values.iter().all(|value| value.is_finite()), "every value must be a finite number");
+        check(self.gravity < 0.0, "gravity must be negative");
+        check(self.jump_velocity > 0.0, "jump_velocity must be positive");
+        check(self.obstacle_speed < 0.0, "obstacle_speed must be negative");
+        check(self.min_spawn_time > 0.0, "min_spawn_time must be positive");
+        check(
+            self.max_spawn_time >= self.min_spawn_time,
+            "max_spawn_time must not be below min_spawn_time",
+        );
+        check(self.ceiling_y > self.ground_y, "ceiling_y must be above ground_y");
+        check(self.flip_speed > 0.0, "flip_speed must be positive");
+        check(
+            self.jump_buffer_time >= 0.0 && self.coyote_time >= 0.0 && self.flip_buffer_time >= 0.0,
+            "buffer and coyote times must not be negative",
+        );
+        check(
+            (0.0..=1.0).contains(&self.jump_release_cut),
+            "jump_release_cut must be between 0 and 1",
+        );
+        check(self.coin_hitbox_size > 0.0, "coin_hitbox_size must be positive");
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join(", "))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<GameConfig, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+/// Loads `GameConfig` from a tuning asset and keeps the resource in sync with it.
+/// Without this plugin (e.g. headless) the built-in defaults are used.
+pub struct ConfigPlugin {
+    pub path: String,
+}
+
+impl Default for ConfigPlugin {
+    fn default() -> Self {
+        Self {
+            path: "game.tuning.ron".to_string(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct GameConfigHandle(Handle<GameConfig>);
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .init_resource::<GameConfig>()
+            .add_systems(Update, apply_config_changes);
+
+        let handle = app.world.resource::<AssetServer>().load(self.path.clone());
+        app.insert_resource(GameConfigHandle(handle));
+    }
+}
+
+/// Copies the tuning asset into `GameConfig` whenever it (re)loads. An edit made during a
+/// run waits until the run is over, since its recording only holds the starting tuning.
+fn apply_config_changes(
+    mut events: EventReader<AssetEvent<GameConfig>>,
+    handle: Res<GameConfigHandle>,
+    assets: Res<Assets<GameConfig>>,
+    mut config: ResMut<GameConfig>,
+    active_run: Option<Res<ActiveRun>>,
+    mut pending: Local<bool>,
+) {
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+            if active_run.is_some() && !*pending {
+                println!("🔧 Game tuning changed; applying it once this run is over");
+            }
+            *pending = true;
+        }
+    }
+    if !*pending || active_run.is_some() {
+        return;
+    }
+    *pending = false;
+
+    if let Some(loaded) = assets.get(&handle.0) {
+        // A bad edit keeps the tuning that was working
+        if let Err(err) = loaded.validate() {
+            eprintln!("⚠️ Invalid game tuning: {err}; keeping the previous values");
+            return;
+        }
+        *config = loaded.clone();
+        println!("🔧 Game tuning (re)loaded");
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+/// Things that happen during a run that something outside the run (the serial
+/// controller, for one) may want to react to.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    /// Sent every tick the score grows; listeners throttle it themselves.
+    ScoreChanged(u32),
+    CoinCollected { total: u32 },
+    PlayerDied,
+    /// The player flipped to the ceiling (`to_ceiling`) or back to the ground.
+    Flipped { to_ceiling: bool },
+}
+
+/// Where and when two hitboxes met during a tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// How far into the tick they met, 0..=1. 0 if they already overlapped when it started.
+    pub time: f32,
+    /// Unit normal of the side that was hit, pointing back towards the player.
+    pub normal: Vec2,
+    /// The player's translation at the moment of contact.
+    pub position: Vec2,
+}
+
+/// The player's hitbox touched a `Hazard`'s.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerHitHazard {
+    pub player: Entity,
+    pub hazard: Entity,
+    pub contact: Contact,
+}
+
+/// The player's hitbox touched a `Pickup`'s.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerTouchedPickup {
+    pub player: Entity,
+    pub pickup: Entity,
+    pub contact: Contact,
+}
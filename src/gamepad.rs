@@ -0,0 +1,100 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+
+use crate::input::PlayerGamepad;
+use crate::resources::GameAssets;
+
+#[derive(Component)]
+pub struct GamepadIndicator;
+
+/// Notices gamepads being plugged in and out and lets the player claim one. Their
+/// buttons and sticks reach the game through `ActionState`.
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerGamepad>()
+            .add_systems(Startup, spawn_gamepad_indicator)
+            .add_systems(Update, (track_gamepad_connections, assign_player_gamepad).chain())
+            .add_systems(Update, update_gamepad_indicator.after(assign_player_gamepad));
+    }
+}
+
+pub fn track_gamepad_connections(
+    mut events: EventReader<GamepadConnectionEvent>,
+    mut player: ResMut<PlayerGamepad>,
+) {
+    for event in events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                println!("🎮 Gamepad connected: {} ({:?})", info.name, event.gamepad);
+            }
+            GamepadConnection::Disconnected => {
+                println!("🎮 Gamepad disconnected ({:?})", event.gamepad);
+                // Free the slot so whichever pad is used next takes over
+                if player.0 == Some(event.gamepad) {
+                    player.0 = None;
+                }
+            }
+        }
+    }
+}
+
+/// With no pad assigned, the first one to press a button becomes the player's.
+pub fn assign_player_gamepad(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut player: ResMut<PlayerGamepad>,
+) {
+    // Skip the frame the slot was freed, so the press that freed it doesn't claim it again
+    if player.0.is_some() || player.is_changed() {
+        return;
+    }
+    if let Some(pressed) = buttons.get_just_pressed().find(|button| gamepads.contains(button.gamepad)) {
+        player.0 = Some(pressed.gamepad);
+        let name = gamepads.name(pressed.gamepad).unwrap_or("gamepad");
+        println!("🎮 {name} assigned to the player");
+    }
+}
+
+pub fn spawn_gamepad_indicator(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+        )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(8.0),
+                right: Val::Px(8.0),
+                ..default()
+            }),
+        GamepadIndicator,
+    ));
+}
+
+pub fn update_gamepad_indicator(
+    gamepads: Res<Gamepads>,
+    player: Res<PlayerGamepad>,
+    mut query: Query<&mut Text, With<GamepadIndicator>>,
+) {
+    if !gamepads.is_changed() && !player.is_changed() {
+        return;
+    }
+
+    let connected = gamepads.iter().count();
+    let label = match player.0 {
+        Some(gamepad) => format!("Gamepad: {}", gamepads.name(gamepad).unwrap_or("unknown")),
+        None if connected > 1 => format!("{connected} gamepads - press a button on yours"),
+        None if connected == 1 => "Gamepad connected".to_string(),
+        None => String::new(),
+    };
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = label.clone();
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::resources::GameState;
+use crate::systems::countdown::CountdownSettings;
+
+/// Runs the gameplay loop without a window, renderer or asset server.
+///
+/// Pair it with `GamePlugin` (not the UI plugins). Each `App::update` advances
+/// the clock by exactly `tick`, so a run is simulated as fast as the CPU allows
+/// and the result can be read back from `Score`, `CoinWallet` and `State<GameState>`.
+pub struct HeadlessPlugin {
+    pub tick: Duration,
+}
+
+impl Default for HeadlessPlugin {
+    fn default() -> Self {
+        Self {
+            tick: Duration::from_secs_f64(1.0 / 60.0),
+        }
+    }
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((MinimalPlugins, InputPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(self.tick))
+            .insert_resource(CountdownSettings { seconds: 0.0 })
+            .add_systems(Startup, start_run);
+    }
+}
+
+/// There is no menu to click through, so go straight into a run.
+pub fn start_run(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Countdown);
+}
+
+/// Ends a headless simulation once the run is over.
+pub fn exit_on_game_over(mut exit: EventWriter<AppExit>) {
+    exit.send(AppExit);
+}
+
+/// Builds an app that simulates the game headlessly at the given tick length.
+/// The fixed timestep matches `tick`, so every `App::update` runs exactly one gameplay tick.
+pub fn headless_app(tick: Duration) -> App {
+    let mut app = App::new();
+    app.add_plugins((HeadlessPlugin { tick }, crate::GamePlugin { timestep: tick }));
+    app
+}
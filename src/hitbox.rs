@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+
+use crate::components::Player;
+use crate::input::{Action, ActionState};
+use crate::systems::interpolation::interpolate_translation;
+
+/// Outline of a hitbox around the entity's center, before it is flipped, turned
+/// and scaled with the entity.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Box of this size
+    Box(Vec2),
+    /// Convex polygon, its points in order around the outline
+    Polygon(Vec<Vec2>),
+}
+
+impl Shape {
+    pub fn points(&self) -> Vec<Vec2> {
+        match self {
+            Shape::Box(size) => {
+                let half = *size / 2.0;
+                vec![
+                    Vec2::new(-half.x, -half.y),
+                    Vec2::new(half.x, -half.y),
+                    Vec2::new(half.x, half.y),
+                    Vec2::new(-half.x, half.y),
+                ]
+            }
+            Shape::Polygon(points) => points.clone(),
+        }
+    }
+}
+
+/// Area of an entity that takes part in collisions: `shape` moved by `offset` from
+/// the entity's translation. Both turn with the entity and mirror with its sprite.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Hitbox {
+    pub offset: Vec2,
+    pub shape: Shape,
+}
+
+impl Hitbox {
+    /// A box of `size` centered on the entity.
+    pub fn new(size: Vec2) -> Self {
+        Self { offset: Vec2::ZERO, shape: Shape::Box(size) }
+    }
+
+    /// A convex polygon around the entity's center.
+    pub fn polygon(points: &[Vec2]) -> Self {
+        Self { offset: Vec2::ZERO, shape: Shape::Polygon(points.to_vec()) }
+    }
+
+    pub fn with_offset(self, offset: Vec2) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// The outline in world space for an entity at `transform`, mirrored like its
+    /// sprite, e.g. a ceiling spike drawn with `flip_y`.
+    pub fn world_points(&self, transform: &Transform, sprite: Option<&Sprite>) -> Vec<Vec2> {
+        let mirror = Vec2::new(
+            if sprite.is_some_and(|sprite| sprite.flip_x) { -1.0 } else { 1.0 },
+            if sprite.is_some_and(|sprite| sprite.flip_y) { -1.0 } else { 1.0 },
+        );
+        self.shape
+            .points()
+            .into_iter()
+            .map(|point| {
+                let local = (point + self.offset) * mirror;
+                transform.transform_point(local.extend(0.0)).truncate()
+            })
+            .collect()
+    }
+}
+
+/// Separating axis test between two convex outlines. Outlines that only touch don't
+/// overlap.
+pub fn overlaps(a: &[Vec2], b: &[Vec2]) -> bool {
+    sweep(a, Vec2::ZERO, b).is_some()
+}
+
+/// First moment `a` overlaps `b` as it moves by `motion`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of `motion` covered when they meet, 0 if they overlap from the start.
+    pub time: f32,
+    /// Unit normal of `b`'s side that was hit, pointing towards `a`.
+    pub normal: Vec2,
+}
+
+/// Where `a` first overlaps `b` on its way as it moves by `motion`, so fast movement
+/// can't skip past `b`. For two moving outlines, pass `a`'s motion relative to `b`.
+pub fn sweep(a: &[Vec2], motion: Vec2, b: &[Vec2]) -> Option<SweepHit> {
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+    // Without motion to go by, the side a overlaps the least
+    let mut shallowest = (f32::INFINITY, Vec2::ZERO);
+
+    // The swept outline's sides are a's own and two parallel to the motion
+    let axes = edge_normals(a)
+        .chain(edge_normals(b))
+        .chain(Some(motion.perp()).filter(|normal| *normal != Vec2::ZERO));
+    for axis in axes {
+        let axis = axis.normalize();
+        let (a_min, a_max) = project(a, axis);
+        let (b_min, b_max) = project(b, axis);
+        let speed = motion.dot(axis);
+
+        if speed == 0.0 {
+            if a_max <= b_min || b_max <= a_min {
+                return None;
+            }
+            let (depth, side) = if a_max - b_min < b_max - a_min {
+                (a_max - b_min, -axis)
+            } else {
+                (b_max - a_min, axis)
+            };
+            if depth < shallowest.0 {
+                shallowest = (depth, side);
+            }
+            continue;
+        }
+        // When a's projection starts and stops overlapping b's along this axis
+        let (start, end) = if speed > 0.0 {
+            ((b_min - a_max) / speed, (b_max - a_min) / speed)
+        } else {
+            ((b_max - a_min) / speed, (b_min - a_max) / speed)
+        };
+        if start > enter {
+            enter = start;
+            normal = if speed > 0.0 { -axis } else { axis };
+        }
+        exit = exit.min(end);
+    }
+
+    (enter < exit && enter < 1.0 && exit > 0.0).then(|| SweepHit {
+        time: enter.max(0.0),
+        normal: if normal == Vec2::ZERO { shallowest.1 } else { normal },
+    })
+}
+
+fn edge_normals(points: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(start, end)| (*end - *start).perp())
+        .filter(|normal| *normal != Vec2::ZERO)
+}
+
+fn project(points: &[Vec2], axis: Vec2) -> (f32, f32) {
+    points.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), point| {
+        let distance = point.dot(axis);
+        (min.min(distance), max.max(distance))
+    })
+}
+
+/// Whether hitboxes are drawn over the game, toggled with `Action::ToggleHitboxes`.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShowHitboxes(pub bool);
+
+/// Draws every hitbox as an outline while `ShowHitboxes` is on.
+pub struct HitboxDebugPlugin;
+
+impl Plugin for HitboxDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowHitboxes>()
+            .add_systems(Update, toggle_hitboxes)
+            // Drawn where the sprites are, after they were interpolated
+            .add_systems(
+                PostUpdate,
+                draw_hitboxes
+                    .after(interpolate_translation)
+                    .run_if(|show: Res<ShowHitboxes>| show.0),
+            );
+    }
+}
+
+pub fn toggle_hitboxes(actions: Res<ActionState>, mut show: ResMut<ShowHitboxes>) {
+    if actions.just_pressed(Action::ToggleHitboxes) {
+        show.0 = !show.0;
+        println!("🔲 Hitboxes {}", if show.0 { "shown" } else { "hidden" });
+    }
+}
+
+pub fn draw_hitboxes(
+    mut gizmos: Gizmos,
+    query: Query<(&Transform, &Hitbox, Option<&Sprite>, Has<Player>)>,
+) {
+    for (transform, hitbox, sprite, is_player) in query.iter() {
+        let points = hitbox.world_points(transform, sprite);
+        let color = if is_player { Color::LIME_GREEN } else { Color::RED };
+        // Closed outline, back to the first point
+        gizmos.linestrip_2d(points.iter().chain(points.first()).copied(), color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(center: Vec2, size: f32) -> Vec<Vec2> {
+        Shape::Box(Vec2::splat(size)).points().into_iter().map(|point| point + center).collect()
+    }
+
+    #[test]
+    fn sweep_reports_when_and_where_it_hits() {
+        let hit = sweep(&square(Vec2::ZERO, 10.0), Vec2::new(40.0, 0.0), &square(Vec2::new(30.0, 0.0), 10.0))
+            .expect("moves into the box");
+        assert!((hit.time - 0.5).abs() < 1e-5);
+        assert_eq!(hit.normal, Vec2::NEG_X);
+
+        let hit = sweep(&square(Vec2::new(0.0, 30.0), 10.0), Vec2::new(0.0, -40.0), &square(Vec2::ZERO, 10.0))
+            .expect("falls onto the box");
+        assert!((hit.time - 0.5).abs() < 1e-5);
+        assert_eq!(hit.normal, Vec2::Y);
+    }
+
+    #[test]
+    fn touching_is_not_a_hit() {
+        let a = square(Vec2::ZERO, 10.0);
+        let b = square(Vec2::new(30.0, 0.0), 10.0);
+        // Stops exactly against b's side
+        assert_eq!(sweep(&a, Vec2::new(20.0, 0.0), &b), None);
+        // Slides along b's top edge
+        assert_eq!(sweep(&square(Vec2::new(0.0, 10.0), 10.0), Vec2::new(60.0, 0.0), &b), None);
+        assert!(!overlaps(&square(Vec2::new(20.0, 0.0), 10.0), &b));
+        assert!(overlaps(&square(Vec2::new(21.0, 0.0), 10.0), &b));
+    }
+
+    #[test]
+    fn large_motion_through_a_thin_box_still_hits() {
+        let a = square(Vec2::ZERO, 10.0);
+        let wall: Vec<Vec2> = Shape::Box(Vec2::new(1.0, 100.0))
+            .points()
+            .into_iter()
+            .map(|point| point + Vec2::new(500.0, 0.0))
+            .collect();
+        let motion = Vec2::new(1000.0, 0.0);
+        assert!(!overlaps(&a, &wall));
+        assert!(!overlaps(&a.iter().map(|point| *point + motion).collect::<Vec<_>>(), &wall));
+
+        let hit = sweep(&a, motion, &wall).expect("passes through the wall");
+        assert!((hit.time - 0.4945).abs() < 1e-4);
+        assert_eq!(hit.normal, Vec2::NEG_X);
+    }
+}
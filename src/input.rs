@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::ron_file::{load_or_default, load_ron, save_ron};
+
+/// Where bindings are read from at startup and saved to when rebound in the menu.
+pub const BINDINGS_PATH: &str = "bindings.ron";
+
+/// Everything the player can ask the game to do. Gameplay and menus only look at
+/// these, never at keys or buttons directly.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    Jump,
+    Flip,
+    Pause,
+    Restart,
+    Confirm,
+    Back,
+    NavigateUp,
+    NavigateDown,
+    ToggleHitboxes,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::Jump,
+        Action::Flip,
+        Action::Pause,
+        Action::Restart,
+        Action::Confirm,
+        Action::Back,
+        Action::NavigateUp,
+        Action::NavigateDown,
+        Action::ToggleHitboxes,
+    ];
+}
+
+/// Keys and buttons that trigger one action; any of them will do.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ActionBinding {
+    pub keys: Vec<KeyCode>,
+    pub mouse: Vec<MouseButton>,
+    pub gamepad: Vec<GamepadButtonType>,
+}
+
+/// Bindings for every action, editable in `bindings.ron` or from the Controls menu.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct InputBindings {
+    pub actions: BTreeMap<Action, ActionBinding>,
+    /// How far the left stick must be pushed up or down to navigate menus, 0..=1.
+    pub stick_threshold: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let bind = |keys: &[KeyCode], mouse: &[MouseButton], gamepad: &[GamepadButtonType]| ActionBinding {
+            keys: keys.to_vec(),
+            mouse: mouse.to_vec(),
+            gamepad: gamepad.to_vec(),
+        };
+        use GamepadButtonType as Pad;
+        let actions = BTreeMap::from([
+            (Action::Jump, bind(&[KeyCode::Space], &[MouseButton::Left], &[Pad::South])),
+            (Action::Flip, bind(&[KeyCode::F], &[], &[Pad::West])),
+            (Action::Pause, bind(&[KeyCode::P, KeyCode::Escape], &[], &[Pad::Start])),
+            (Action::Restart, bind(&[KeyCode::R], &[], &[Pad::Select])),
+            (Action::Confirm, bind(&[KeyCode::Return], &[], &[Pad::South])),
+            (Action::Back, bind(&[KeyCode::Back, KeyCode::Escape], &[], &[Pad::East])),
+            (Action::NavigateUp, bind(&[KeyCode::Up, KeyCode::W], &[], &[Pad::DPadUp])),
+            (Action::NavigateDown, bind(&[KeyCode::Down, KeyCode::S], &[], &[Pad::DPadDown])),
+            (Action::ToggleHitboxes, bind(&[KeyCode::F3], &[], &[])),
+        ]);
+        Self {
+            actions,
+            stick_threshold: 0.5,
+        }
+    }
+}
+
+impl InputBindings {
+    /// Actions missing from the file keep their default binding.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut bindings: Self = load_ron(path, "bindings")?;
+        for (action, binding) in Self::default().actions {
+            bindings.actions.entry(action).or_insert(binding);
+        }
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(self, path)
+    }
+
+    /// The saved bindings if there are any, otherwise the defaults.
+    pub fn load_or_default(path: &Path) -> Self {
+        load_or_default(path, "bindings", Self::load)
+    }
+
+    pub fn binding(&self, action: Action) -> ActionBinding {
+        self.actions.get(&action).cloned().unwrap_or_default()
+    }
+}
+
+/// The gamepad that drives the game. Until one is assigned, every pad does, and the
+/// first to press a button becomes the player's.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerGamepad(pub Option<Gamepad>);
+
+/// Which actions are held, and which started or stopped this frame.
+#[derive(Resource, Default, Debug)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+    /// Held through a bound key or button as of the last read
+    held_by_device: HashSet<Action>,
+    /// Held by a source without a device to poll, e.g. the serial controller
+    held_by_source: HashSet<Action>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    /// A one-off press for this frame, from a source that only reports taps.
+    pub fn press(&mut self, action: Action) {
+        self.just_pressed.insert(action);
+    }
+
+    /// Holds or lets go of an action for a source that reports its own press edges
+    /// (the serial controller queues its jumps per tick), so holding starts no
+    /// `just_pressed`; letting go does end in `just_released`.
+    pub fn hold(&mut self, action: Action, held: bool) {
+        if held {
+            self.held_by_source.insert(action);
+            self.pressed.insert(action);
+        } else if self.held_by_source.remove(&action) && !self.held_by_device.contains(&action) {
+            self.pressed.remove(&action);
+            self.just_released.insert(action);
+        }
+    }
+}
+
+/// Stages of filling `ActionState` each frame, in `PreUpdate`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ActionSet {
+    /// Rebuilt from the bound keys, mouse buttons and gamepad buttons
+    Read,
+    /// Other sources, like the serial controller, add to it
+    Extend,
+}
+
+/// Turns keyboard, mouse and gamepad input into `ActionState` using `InputBindings`.
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .init_resource::<PlayerGamepad>()
+            .insert_resource(InputBindings::load_or_default(Path::new(BINDINGS_PATH)))
+            .configure_sets(PreUpdate, (ActionSet::Read, ActionSet::Extend).chain().after(InputSystem))
+            .add_systems(PreUpdate, read_bound_inputs.in_set(ActionSet::Read));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn read_bound_inputs(
+    mut state: ResMut<ActionState>,
+    bindings: Res<InputBindings>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    player_gamepad: Res<PlayerGamepad>,
+) {
+    let player_pads = || {
+        gamepads
+            .iter()
+            .filter(|gamepad| player_gamepad.0.is_none_or(|assigned| assigned == *gamepad))
+    };
+
+    let mut held_by_device = HashSet::new();
+    for (action, binding) in &bindings.actions {
+        let pad_pressed = player_pads().any(|gamepad| {
+            binding
+                .gamepad
+                .iter()
+                .any(|button| gamepad_buttons.pressed(GamepadButton::new(gamepad, *button)))
+        });
+        if keyboard.any_pressed(binding.keys.iter().copied())
+            || mouse.any_pressed(binding.mouse.iter().copied())
+            || pad_pressed
+        {
+            held_by_device.insert(*action);
+        }
+    }
+
+    // The stick navigates like the d-pad once pushed past the threshold
+    for gamepad in player_pads() {
+        let y = gamepad_axes
+            .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+            .unwrap_or(0.0);
+        if y > bindings.stick_threshold {
+            held_by_device.insert(Action::NavigateUp);
+        } else if y < -bindings.stick_threshold {
+            held_by_device.insert(Action::NavigateDown);
+        }
+    }
+
+    let now: HashSet<Action> = held_by_device.union(&state.held_by_source).copied().collect();
+    state.just_pressed = now.difference(&state.pressed).copied().collect();
+    state.just_released = state.pressed.difference(&now).copied().collect();
+    state.pressed = now;
+    state.held_by_device = held_by_device;
+}
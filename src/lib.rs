@@ -0,0 +1,30 @@
+// src/lib.rs
+
+pub mod config;
+pub mod constants;
+pub mod components;
+pub mod resources;
+pub mod events;
+pub mod input;
+pub mod gamepad;
+pub mod hitbox;
+pub mod serial;
+pub mod systems;
+pub mod plugins;
+pub mod headless;
+pub mod replay;
+pub mod ron_file;
+pub mod cli;
+
+pub use config::ConfigPlugin;
+pub use input::ActionPlugin;
+pub use gamepad::GamepadPlugin;
+pub use hitbox::HitboxDebugPlugin;
+pub use plugins::{GamePlugin, GamePlugins};
+pub use headless::{headless_app, HeadlessPlugin};
+pub use replay::{PlaybackPlugin, RecordPlugin};
+pub use serial::SerialPlugin;
+pub use systems::menu::MenuPlugin;
+pub use systems::navigation::MenuNavigationPlugin;
+pub use systems::pause::PausePlugin;
+pub use systems::shop::ShopPlugin;
@@ -0,0 +1,149 @@
+// src/plugins.rs
+use std::time::Duration;
+
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+use bevy::ecs::schedule::common_conditions::in_state;
+use bevy::transform::TransformSystem;
+
+use crate::config::{ConfigPlugin, GameConfig};
+use crate::resources::*;
+use crate::events::{GameEvent, PlayerHitHazard, PlayerTouchedPickup};
+use crate::input::ActionPlugin;
+use crate::gamepad::GamepadPlugin;
+use crate::hitbox::HitboxDebugPlugin;
+use crate::serial::SerialPlugin;
+use crate::systems::setup::{reset_run_state, setup, RunSetup};
+use crate::systems::cleanup::{despawn_on_exit, despawn_run_entities, end_run};
+use crate::systems::countdown::{
+    hide_go_banner, spawn_go_banner, start_countdown, tick_countdown, CountdownSettings,
+};
+use crate::systems::movement::{player_movement, read_jump_input};
+use crate::systems::interpolation::{
+    interpolate_translation, record_translation, restore_translation, FixedSet,
+};
+use crate::systems::obstacles::{spawn_obstacles, move_obstacles};
+use crate::systems::collision::detect_collisions;
+use crate::systems::game_over::{back_to_menu, die_on_hazard, spawn_game_over_screen};
+use crate::systems::score::{advance_run_tick, update_score};
+use crate::systems::restart::restart_game;
+use crate::systems::coin::{spawn_coins, move_coins, collect_coins, CoinSpawnTimer};
+use crate::systems::menu::MenuPlugin;
+use crate::systems::navigation::MenuNavigationPlugin;
+use crate::systems::pause::PausePlugin;
+use crate::systems::shop::ShopPlugin;
+
+/// Core gameplay: game state, run resources, player, spikes, coins, score and restart.
+///
+/// The simulation steps in `FixedUpdate` every `timestep`, independent of frame rate.
+pub struct GamePlugin {
+    pub timestep: Duration,
+}
+
+impl Default for GamePlugin {
+    fn default() -> Self {
+        Self {
+            timestep: Duration::from_secs_f64(1.0 / 60.0),
+        }
+    }
+}
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ActionPlugin)
+            .add_state::<GameState>()
+            .add_event::<GameEvent>()
+            .add_event::<PlayerHitHazard>()
+            .add_event::<PlayerTouchedPickup>()
+            .insert_resource(Time::<Fixed>::from_duration(self.timestep))
+            .init_resource::<GameConfig>()
+            .init_resource::<JumpRequest>()
+            .init_resource::<JumpRelease>()
+            .init_resource::<InputScale>()
+            .init_resource::<RunTick>()
+            .init_resource::<SpawnTimer>()
+            .init_resource::<Score>()
+            .init_resource::<CoinWallet>()
+            .init_resource::<CurrentSkin>()
+            .init_resource::<GameAssets>()
+            .init_resource::<RngSeed>()
+            .init_resource::<GameRng>()
+            .insert_resource(CoinSpawnTimer(Timer::from_seconds(2.0, TimerMode::Repeating)))
+            .init_resource::<CountdownSettings>()
+            .configure_sets(
+                OnEnter(GameState::Countdown),
+                RunSetup.run_if(not(resource_exists::<ActiveRun>())),
+            )
+            .add_systems(
+                OnEnter(GameState::Countdown),
+                (despawn_run_entities, reset_run_state, setup).chain().in_set(RunSetup),
+            )
+            .add_systems(OnEnter(GameState::Countdown), start_countdown)
+            .add_systems(Update, tick_countdown.run_if(in_state(GameState::Countdown)))
+            .add_systems(OnExit(GameState::Countdown), spawn_go_banner)
+            .add_systems(Update, hide_go_banner.run_if(in_state(GameState::Running)))
+            .add_systems(OnEnter(GameState::Menu), (despawn_run_entities, end_run))
+            .add_systems(OnEnter(GameState::GameOver), (end_run, spawn_game_over_screen))
+            .add_systems(OnExit(GameState::Menu), despawn_on_exit(GameState::Menu))
+            .add_systems(OnExit(GameState::Countdown), despawn_on_exit(GameState::Countdown))
+            .add_systems(OnExit(GameState::Running), despawn_on_exit(GameState::Running))
+            .add_systems(OnExit(GameState::Paused), despawn_on_exit(GameState::Paused))
+            .add_systems(OnExit(GameState::GameOver), despawn_on_exit(GameState::GameOver))
+            .configure_sets(
+                FixedUpdate,
+                (FixedSet::Restore, FixedSet::Simulate, FixedSet::Record).chain(),
+            )
+            .add_systems(FixedUpdate, restore_translation.in_set(FixedSet::Restore))
+            .add_systems(FixedUpdate, record_translation.in_set(FixedSet::Record))
+            // Apply a death right away, so later ticks in the same frame don't keep simulating it
+            .add_systems(
+                FixedUpdate,
+                apply_state_transition::<GameState>.after(FixedSet::Record),
+            )
+            .add_systems(
+                PostUpdate,
+                interpolate_translation.before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(Update, read_jump_input.run_if(in_state(GameState::Running)))
+            .add_systems(
+                FixedUpdate,
+                (
+                    advance_run_tick,
+                    player_movement,
+                    spawn_obstacles,
+                    move_obstacles,
+                    update_score,
+                    spawn_coins,
+                    move_coins,
+                    detect_collisions,
+                    (die_on_hazard, collect_coins),
+                )
+                    .chain()
+                    .in_set(FixedSet::Simulate)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                Update,
+                (restart_game, back_to_menu).run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+/// Every plugin that makes up the full game. Pieces can be disabled through the builder,
+/// e.g. `GamePlugins.build().disable::<ShopPlugin>()`.
+pub struct GamePlugins;
+
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(GamePlugin::default())
+            .add(ConfigPlugin::default())
+            .add(MenuPlugin)
+            .add(PausePlugin)
+            .add(ShopPlugin)
+            .add(MenuNavigationPlugin)
+            .add(SerialPlugin)
+            .add(GamepadPlugin)
+            .add(HitboxDebugPlugin)
+    }
+}
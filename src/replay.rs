@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::ron_file::{load_ron, save_ron};
+use crate::resources::{
+    GameRng, GameState, InputScale, JumpRelease, JumpRequest, RngSeed, RunTick, Score,
+};
+use crate::systems::movement::player_movement;
+use crate::systems::score::advance_run_tick;
+use crate::systems::setup::{reset_run_state, RunSetup};
+
+/// Everything needed to reproduce one run: seed, timestep, tuning, the fixed tick
+/// of every jump press and release and every analog input change. `end_tick` and `final_score` are
+/// filled in when the run ends.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub seed: u64,
+    pub timestep: Duration,
+    pub tuning: GameConfig,
+    pub jumps: Vec<u64>,
+    /// Ticks where jump was let go. Absent in older recordings.
+    #[serde(default)]
+    pub releases: Vec<u64>,
+    /// Ticks where `InputScale` changed, in order. Absent in older recordings.
+    #[serde(default)]
+    pub input_scale: Vec<ScaleChange>,
+    pub end_tick: Option<u64>,
+    pub final_score: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ScaleChange {
+    pub tick: u64,
+    pub jump: f32,
+    pub flip_speed: f32,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let recording: Self = load_ron(path, "recording")?;
+        recording
+            .tuning
+            .validate()
+            .map_err(|err| format!("invalid recording {}: {err}", path.display()))?;
+        Ok(recording)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(self, path)
+    }
+}
+
+/// Records the inputs of every run and writes the most recent one to `path` when it ends.
+pub struct RecordPlugin {
+    pub path: PathBuf,
+}
+
+#[derive(Resource)]
+struct RecordPath(PathBuf);
+
+impl Plugin for RecordPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RecordPath(self.path.clone()))
+            .add_systems(
+                OnEnter(GameState::Countdown),
+                start_recording.after(reset_run_state).in_set(RunSetup),
+            )
+            .add_systems(
+                FixedUpdate,
+                (record_jump, record_input_scale)
+                    .after(advance_run_tick)
+                    .before(player_movement)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), save_recording);
+    }
+}
+
+fn start_recording(
+    mut commands: Commands,
+    rng: Res<GameRng>,
+    fixed_time: Res<Time<Fixed>>,
+    config: Res<GameConfig>,
+) {
+    commands.insert_resource(Recording {
+        seed: rng.seed(),
+        timestep: fixed_time.timestep(),
+        tuning: config.clone(),
+        jumps: Vec::new(),
+        releases: Vec::new(),
+        input_scale: Vec::new(),
+        end_tick: None,
+        final_score: None,
+    });
+}
+
+fn record_jump(
+    jump: Res<JumpRequest>,
+    release: Res<JumpRelease>,
+    tick: Res<RunTick>,
+    mut recording: ResMut<Recording>,
+) {
+    if jump.0 {
+        recording.jumps.push(tick.0);
+    }
+    if release.0 {
+        recording.releases.push(tick.0);
+    }
+}
+
+fn record_input_scale(scale: Res<InputScale>, tick: Res<RunTick>, mut recording: ResMut<Recording>) {
+    let recorded = recording.input_scale.last().map_or(InputScale::default(), |change| InputScale {
+        jump: change.jump,
+        flip_speed: change.flip_speed,
+    });
+    if *scale != recorded {
+        recording.input_scale.push(ScaleChange {
+            tick: tick.0,
+            jump: scale.jump,
+            flip_speed: scale.flip_speed,
+        });
+    }
+}
+
+fn save_recording(
+    mut recording: ResMut<Recording>,
+    path: Res<RecordPath>,
+    tick: Res<RunTick>,
+    score: Res<Score>,
+) {
+    recording.end_tick = Some(tick.0);
+    recording.final_score = Some(score.0);
+
+    match recording.save(&path.0) {
+        Ok(()) => println!("📼 Run recorded to {}", path.0.display()),
+        Err(err) => eprintln!("⚠️ {err}"),
+    }
+}
+
+/// Plays a recording back instead of live input. Add it after `GamePlugin`,
+/// since it replaces the fixed timestep and tuning with the recorded ones.
+pub struct PlaybackPlugin {
+    pub recording: Recording,
+}
+
+/// How a finished playback compared to the recording.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PlaybackResult {
+    pub end_tick: u64,
+    pub final_score: f32,
+    pub matches: bool,
+}
+
+impl Plugin for PlaybackPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.recording.clone())
+            .insert_resource(self.recording.tuning.clone())
+            .insert_resource(RngSeed(Some(self.recording.seed)))
+            .insert_resource(Time::<Fixed>::from_duration(self.recording.timestep))
+            .add_systems(
+                FixedUpdate,
+                (play_jump, play_input_scale)
+                    .after(advance_run_tick)
+                    .before(player_movement)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), check_playback);
+    }
+}
+
+/// Overrides whatever live input arrived with the recorded press and release for this tick
+fn play_jump(
+    recording: Res<Recording>,
+    tick: Res<RunTick>,
+    mut jump: ResMut<JumpRequest>,
+    mut release: ResMut<JumpRelease>,
+) {
+    jump.0 = recording.jumps.binary_search(&tick.0).is_ok();
+    release.0 = recording.releases.binary_search(&tick.0).is_ok();
+}
+
+fn play_input_scale(recording: Res<Recording>, tick: Res<RunTick>, mut scale: ResMut<InputScale>) {
+    let applied = recording.input_scale.partition_point(|change| change.tick <= tick.0);
+    let recorded = match applied.checked_sub(1) {
+        Some(index) => {
+            let change = recording.input_scale[index];
+            InputScale { jump: change.jump, flip_speed: change.flip_speed }
+        }
+        None => InputScale::default(),
+    };
+    scale.set_if_neq(recorded);
+}
+
+fn check_playback(
+    mut commands: Commands,
+    recording: Res<Recording>,
+    tick: Res<RunTick>,
+    score: Res<Score>,
+) {
+    let matches = recording.end_tick == Some(tick.0) && recording.final_score == Some(score.0);
+
+    if matches {
+        println!("📼 Replay matched: died at tick {} with score {:.0}", tick.0, score.0);
+    } else {
+        println!(
+            "📼 Replay diverged: died at tick {} with score {:.0}, recorded {:?} / {:?}",
+            tick.0, score.0, recording.end_tick, recording.final_score
+        );
+    }
+
+    commands.insert_resource(PlaybackResult {
+        end_tick: tick.0,
+        final_score: score.0,
+        matches,
+    });
+}
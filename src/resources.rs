@@ -1,14 +1,54 @@
 use bevy::prelude::*;
 use bevy::ecs::schedule::States;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
 
 #[derive(Resource, States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum GameState {
     #[default]
     Menu,
+    Countdown,
     Running,
+    Paused,
     GameOver,
 }
 
+/// Present while a run is in progress (including while paused). Entering
+/// `Countdown` without it starts a fresh run; with it, the run is resumed.
+#[derive(Resource)]
+pub struct ActiveRun;
+
+/// Set when jump is pressed during a frame and consumed by the next fixed tick,
+/// so a press is neither lost nor applied twice when frame and tick rates differ.
+#[derive(Resource, Default)]
+pub struct JumpRequest(pub bool);
+
+/// Set when jump is let go during a frame and consumed by the next fixed tick, like
+/// `JumpRequest`. Cuts a rising jump short.
+#[derive(Resource, Default)]
+pub struct JumpRelease(pub bool);
+
+/// Multipliers on the tuned jump velocity and flip speed, driven by analog controller
+/// input. Both stay at 1.0 without one.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct InputScale {
+    pub jump: f32,
+    pub flip_speed: f32,
+}
+
+impl Default for InputScale {
+    fn default() -> Self {
+        Self {
+            jump: 1.0,
+            flip_speed: 1.0,
+        }
+    }
+}
+
+/// Fixed ticks simulated since the current run started.
+#[derive(Resource, Default)]
+pub struct RunTick(pub u64);
+
 #[derive(Resource)]
 pub struct SpawnTimer(pub Timer);
 
@@ -27,18 +67,96 @@ impl Default for Score {
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct CoinWallet {
     pub coins: u32,
 }
 
-impl Default for CoinWallet {
+#[derive(Resource)]
+pub struct CurrentSkin {
+    pub color: Color,
+}
+
+impl Default for CurrentSkin {
     fn default() -> Self {
-        Self { coins: 0 }
+        Self { color: Color::WHITE }
     }
 }
 
+/// Handles to every asset gameplay spawns with. Without an `AssetServer`
+/// (headless runs) all handles are left at their defaults.
 #[derive(Resource)]
-pub struct CurrentSkin {
-    pub color: Color,
+pub struct GameAssets {
+    pub player: Handle<Image>,
+    pub spike: Handle<Image>,
+    pub coin: Handle<Image>,
+    pub background: Handle<Image>,
+    pub font: Handle<Font>,
+}
+
+impl FromWorld for GameAssets {
+    fn from_world(world: &mut World) -> Self {
+        let Some(asset_server) = world.get_resource::<AssetServer>() else {
+            return Self {
+                player: Handle::default(),
+                spike: Handle::default(),
+                coin: Handle::default(),
+                background: Handle::default(),
+                font: Handle::default(),
+            };
+        };
+
+        Self {
+            player: asset_server.load("player.png"),
+            spike: asset_server.load("spike.png"),
+            coin: asset_server.load("coin.png"),
+            background: asset_server.load("background.png"),
+            font: asset_server.load("FiraSans-Bold.ttf"),
+        }
+    }
+}
+
+/// Seed for every run. `None` picks a fresh random seed each time a run starts.
+#[derive(Resource, Default)]
+pub struct RngSeed(pub Option<u64>);
+
+/// The only source of gameplay randomness. Reseeded at the start of every run,
+/// so a run is fully determined by its seed.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst)
+    }
 }
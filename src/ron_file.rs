@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Reads a RON file; `what` names its contents in the error, e.g. "recording".
+pub fn load_ron<T: DeserializeOwned>(path: &Path, what: &str) -> Result<T, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+    ron::from_str(&text).map_err(|err| format!("invalid {what} {}: {err}", path.display()))
+}
+
+pub fn save_ron<T: Serialize>(value: &T, path: &Path) -> Result<(), String> {
+    let text = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())?;
+    fs::write(path, text).map_err(|err| format!("could not write {}: {err}", path.display()))
+}
+
+/// What `load` reads from `path` if the file exists, otherwise the defaults. A file
+/// that can't be loaded is reported and replaced by the defaults too.
+pub fn load_or_default<T: Default>(
+    path: &Path,
+    what: &str,
+    load: impl FnOnce(&Path) -> Result<T, String>,
+) -> T {
+    if !path.exists() {
+        return T::default();
+    }
+    load(path).unwrap_or_else(|err| {
+        eprintln!("⚠️ {err}; using default {what}");
+        T::default()
+    })
+}
@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::InputScale;
+use crate::systems::countdown::CountdownSettings;
+use crate::ron_file::{load_or_default, load_ron, save_ron};
+
+/// Where calibration is read from at startup and saved to from the calibration screen.
+pub const ANALOG_CONFIG_PATH: &str = "controller.ron";
+
+/// What an analog channel controls. Scales are multipliers on the tuned value,
+/// reached at the bottom and top of the calibrated range.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum AnalogMapping {
+    Unused,
+    JumpStrength { min_scale: f32, max_scale: f32 },
+    FlipSpeed { min_scale: f32, max_scale: f32 },
+    /// The countdown length slider in the main menu
+    MenuSlider,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ChannelCalibration {
+    /// Raw readings at rest and at full travel; either may be the larger one.
+    pub min: i32,
+    pub max: i32,
+    /// Fraction of the range at the bottom that reads as zero.
+    pub deadzone: f32,
+    /// Fraction of the previous filtered value kept per reading, 0 for none.
+    pub smoothing: f32,
+    pub mapping: AnalogMapping,
+}
+
+impl Default for ChannelCalibration {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: 1023,
+            deadzone: 0.05,
+            smoothing: 0.5,
+            mapping: AnalogMapping::Unused,
+        }
+    }
+}
+
+impl ChannelCalibration {
+    /// Maps a raw reading into 0..=1 with the deadzone cut off.
+    pub fn normalize(&self, raw: i32) -> f32 {
+        if self.max == self.min {
+            return 0.0;
+        }
+        // Wide enough for any range a hand-edited calibration file can hold
+        let value = ((i64::from(raw) - i64::from(self.min)) as f64
+            / (i64::from(self.max) - i64::from(self.min)) as f64)
+            .clamp(0.0, 1.0) as f32;
+        if value <= self.deadzone {
+            0.0
+        } else {
+            (value - self.deadzone) / (1.0 - self.deadzone)
+        }
+    }
+}
+
+/// Calibration and mapping of every analog channel, by channel number.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AnalogSettings {
+    pub channels: BTreeMap<u8, ChannelCalibration>,
+}
+
+impl Default for AnalogSettings {
+    /// Pressure pad on channel 0 for jump strength, potentiometer on 1 for flip speed.
+    fn default() -> Self {
+        let channels = BTreeMap::from([
+            (
+                0,
+                ChannelCalibration {
+                    mapping: AnalogMapping::JumpStrength { min_scale: 0.6, max_scale: 1.4 },
+                    ..default()
+                },
+            ),
+            (
+                1,
+                ChannelCalibration {
+                    mapping: AnalogMapping::FlipSpeed { min_scale: 0.5, max_scale: 2.0 },
+                    ..default()
+                },
+            ),
+        ]);
+        Self { channels }
+    }
+}
+
+impl AnalogSettings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        load_ron(path, "calibration")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(self, path)
+    }
+
+    /// The saved calibration if there is one, otherwise the defaults.
+    pub fn load_or_default(path: &Path) -> Self {
+        load_or_default(path, "calibration", Self::load)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChannelReading {
+    pub raw: i32,
+    pub normalized: f32,
+    pub filtered: f32,
+}
+
+/// Latest reading of every channel heard from, plus the range seen while calibrating.
+#[derive(Resource, Default)]
+pub struct AnalogInputs {
+    pub readings: BTreeMap<u8, ChannelReading>,
+    /// Position of the channel mapped to `MenuSlider`, 0..=1, once it has been heard from.
+    pub slider: Option<f32>,
+    /// Lowest and highest raw reading per channel since calibration started.
+    pub capture: Option<BTreeMap<u8, (i32, i32)>>,
+}
+
+impl AnalogInputs {
+    pub fn update(&mut self, channel: u8, raw: i32, settings: &AnalogSettings) {
+        if let Some(capture) = &mut self.capture {
+            let range = capture.entry(channel).or_insert((raw, raw));
+            range.0 = range.0.min(raw);
+            range.1 = range.1.max(raw);
+        }
+
+        let calibration = settings.channels.get(&channel).cloned().unwrap_or_default();
+        let normalized = calibration.normalize(raw);
+        let reading = self.readings.entry(channel).or_insert(ChannelReading {
+            filtered: normalized,
+            ..default()
+        });
+        let keep = calibration.smoothing.clamp(0.0, 0.99);
+        reading.raw = raw;
+        reading.normalized = normalized;
+        reading.filtered = reading.filtered * keep + normalized * (1.0 - keep);
+
+        if calibration.mapping == AnalogMapping::MenuSlider {
+            self.slider = Some(reading.filtered);
+        }
+    }
+
+    pub fn start_capture(&mut self) {
+        self.capture = Some(BTreeMap::new());
+    }
+
+    /// Ends calibration, storing each channel's observed range. Channels that didn't
+    /// move keep their old range.
+    pub fn finish_capture(&mut self, settings: &mut AnalogSettings) {
+        let Some(capture) = self.capture.take() else { return };
+        for (channel, (min, max)) in capture {
+            if min == max {
+                continue;
+            }
+            let calibration = settings.channels.entry(channel).or_default();
+            // Keep the channel's direction, e.g. a pot wired so that up reads low
+            if calibration.min <= calibration.max {
+                (calibration.min, calibration.max) = (min, max);
+            } else {
+                (calibration.min, calibration.max) = (max, min);
+            }
+        }
+    }
+}
+
+/// Turns the filtered readings into jump and flip multipliers. Unmapped or silent
+/// channels leave their multiplier at 1.0.
+pub fn apply_analog_mappings(
+    inputs: Res<AnalogInputs>,
+    settings: Res<AnalogSettings>,
+    mut scale: ResMut<InputScale>,
+) {
+    if !inputs.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let mut next = InputScale::default();
+    for (channel, calibration) in &settings.channels {
+        let Some(reading) = inputs.readings.get(channel) else { continue };
+        let lerp = |min: f32, max: f32| min + (max - min) * reading.filtered;
+        match calibration.mapping {
+            AnalogMapping::JumpStrength { min_scale, max_scale } => next.jump = lerp(min_scale, max_scale),
+            AnalogMapping::FlipSpeed { min_scale, max_scale } => {
+                next.flip_speed = lerp(min_scale, max_scale)
+            }
+            AnalogMapping::MenuSlider | AnalogMapping::Unused => {}
+        }
+    }
+    scale.set_if_neq(next);
+}
+
+/// Lets the `MenuSlider` channel set the countdown length while in the main menu.
+pub fn apply_menu_slider(inputs: Res<AnalogInputs>, mut countdown: ResMut<CountdownSettings>) {
+    if !inputs.is_changed() {
+        return;
+    }
+    let Some(slider) = inputs.slider else { return };
+    let seconds = CountdownSettings::from_slider(slider).seconds;
+    if countdown.seconds != seconds {
+        countdown.seconds = seconds;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_survives_extreme_ranges() {
+        let calibration = ChannelCalibration { min: i32::MIN, max: i32::MAX, deadzone: 0.0, ..default() };
+        assert!((calibration.normalize(0) - 0.5).abs() < 1e-6);
+        assert_eq!(calibration.normalize(i32::MAX), 1.0);
+
+        // Wired so that up reads low
+        let reversed = ChannelCalibration { min: 1023, max: 0, deadzone: 0.0, ..default() };
+        assert_eq!(reversed.normalize(0), 1.0);
+        assert_eq!(reversed.normalize(i32::MIN), 1.0);
+        assert_eq!(reversed.normalize(2000), 0.0);
+    }
+}
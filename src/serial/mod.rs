@@ -0,0 +1,613 @@
+pub mod analog;
+pub mod protocol;
+#[cfg(unix)]
+pub mod sim;
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serialport::{SerialPort, SerialPortInfo, SerialPortType};
+
+use crate::events::GameEvent;
+use crate::resources::{ActiveRun, GameAssets, GameState, InputScale, JumpRequest};
+use crate::input::{Action, ActionSet, ActionState};
+use crate::systems::interpolation::FixedSet;
+use crate::systems::score::advance_run_tick;
+use analog::{apply_analog_mappings, apply_menu_slider, AnalogInputs, AnalogSettings, ANALOG_CONFIG_PATH};
+use protocol::{parse_line, ControllerMessage, GameMessage, GAME_ID, PROTOCOL_VERSION};
+
+const MIN_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+/// Read timeout, which is also how long a queued outgoing line may wait while the controller is quiet
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// Send `PING` after this long without hearing from a handshaken controller
+const PING_AFTER: Duration = Duration::from_secs(2);
+/// Drop the connection after this long without hearing from a handshaken controller
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(6);
+/// Move on from a scanned port that sent neither `HELLO` nor a valid line in this long.
+/// Long enough for a board that resets when opened to boot and say hello.
+const IDENTIFY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A command from the controller and when its line came in.
+#[derive(Debug, Clone)]
+pub struct ReceivedMessage {
+    pub message: ControllerMessage,
+    pub received_at: Instant,
+}
+
+/// Gameplay and menu commands from the listener thread, in arrival order.
+#[derive(Resource)]
+pub struct ControllerInbox {
+    sender: Sender<ReceivedMessage>,
+    receiver: Mutex<Receiver<ReceivedMessage>>,
+}
+
+impl Default for ControllerInbox {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+/// Controller jumps waiting for a tick, by arrival time. Each tick applies one, so
+/// presses that land in the same frame become jumps on consecutive ticks.
+#[derive(Resource, Default)]
+pub struct PendingJumps(pub VecDeque<Instant>);
+
+/// Time from a controller line arriving to its jump being applied in a tick.
+#[derive(Resource, Default, Debug)]
+pub struct InputLatency {
+    pub last: Option<Duration>,
+    /// Exponential moving average, so one slow frame doesn't dominate
+    pub average: Option<Duration>,
+}
+
+impl InputLatency {
+    fn record(&mut self, latency: Duration) {
+        self.last = Some(latency);
+        self.average = Some(match self.average {
+            Some(average) => average.mul_f32(0.9) + latency.mul_f32(0.1),
+            None => latency,
+        });
+    }
+}
+
+/// Lines waiting to be written to the controller, oldest first.
+#[derive(Resource, Default)]
+pub struct ControllerOutbox(pub Arc<Mutex<Vec<GameMessage>>>);
+
+/// Line counters kept by the listener thread.
+#[derive(Debug, Clone, Default)]
+pub struct SerialStats {
+    pub lines: u64,
+    pub malformed: u64,
+}
+
+#[derive(Resource, Default)]
+pub struct SharedSerialStats(pub Arc<Mutex<SerialStats>>);
+
+/// How to find the controller. With no `port`, USB serial ports matching `vid`/`pid`
+/// (when set) are tried in turn. Without `vid`/`pid` any USB serial port matches, so
+/// one is only kept once it has proven to be a controller.
+#[derive(Resource, Clone)]
+pub struct SerialSettings {
+    pub port: Option<String>,
+    pub baud: u32,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    /// Most `SCORE` lines sent per second
+    pub score_rate: f32,
+}
+
+impl Default for SerialSettings {
+    fn default() -> Self {
+        Self {
+            port: None,
+            baud: 115200,
+            vid: None,
+            pid: None,
+            score_rate: 4.0,
+        }
+    }
+}
+
+impl SerialSettings {
+    fn matches(&self, info: &SerialPortInfo) -> bool {
+        match &info.port_type {
+            SerialPortType::UsbPort(usb) => {
+                self.vid.is_none_or(|vid| vid == usb.vid)
+                    && self.pid.is_none_or(|pid| pid == usb.pid)
+            }
+            // Without udev metadata, fall back to the usual Linux USB-serial names
+            _ => {
+                self.vid.is_none()
+                    && self.pid.is_none()
+                    && (info.port_name.starts_with("/dev/ttyUSB")
+                        || info.port_name.starts_with("/dev/ttyACM"))
+            }
+        }
+    }
+
+    fn candidates(&self) -> Vec<String> {
+        if let Some(port) = &self.port {
+            return vec![port.clone()];
+        }
+
+        serialport::available_ports()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|info| self.matches(info))
+            .map(|info| info.port_name)
+            .collect()
+    }
+
+    /// Whether a port found with these settings is the controller without it having
+    /// to say so first.
+    fn picks_controller(&self) -> bool {
+        self.port.is_some() || self.vid.is_some() || self.pid.is_some()
+    }
+}
+
+/// Whether the controller is currently plugged in and open. `device` is set
+/// once it has introduced itself with `HELLO`.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ControllerStatus {
+    #[default]
+    Disconnected,
+    Connected { port: String, device: Option<String> },
+}
+
+/// Written by the manager thread and mirrored into `ControllerStatus` each frame.
+#[derive(Resource, Default)]
+pub struct SharedControllerStatus(pub Arc<Mutex<ControllerStatus>>);
+
+#[derive(Component)]
+pub struct ControllerIndicator;
+
+/// Keeps a connection to the controller on `SerialSettings`, reconnecting when it is
+/// unplugged, and lets it drive the game: jumps and flips, pause, start and menu
+/// navigation (see `protocol`). Besides its jumps, which are queued per tick, it
+/// acts through `ActionState` like any other input device.
+pub struct SerialPlugin;
+
+impl Plugin for SerialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControllerInbox>()
+            .init_resource::<ControllerOutbox>()
+            .init_resource::<SerialSettings>()
+            .init_resource::<SharedControllerStatus>()
+            .init_resource::<ControllerStatus>()
+            .init_resource::<SharedSerialStats>()
+            .init_resource::<PendingJumps>()
+            .init_resource::<InputLatency>()
+            .init_resource::<AnalogInputs>()
+            .init_resource::<InputScale>()
+            .insert_resource(AnalogSettings::load_or_default(std::path::Path::new(ANALOG_CONFIG_PATH)))
+            .init_resource::<JumpRequest>()
+            .add_event::<GameEvent>()
+            .add_systems(Startup, (setup_serial_listener, spawn_controller_indicator))
+            .add_systems(PreUpdate, apply_controller_messages.in_set(ActionSet::Extend))
+            .add_systems(Update, (apply_analog_mappings, sync_controller_status))
+            .add_systems(Update, apply_menu_slider.run_if(in_state(GameState::Menu)))
+            // Before the tick counter moves, so a recording sees the jump on the tick it lands
+            .add_systems(
+                FixedUpdate,
+                feed_controller_jumps
+                    .before(advance_run_tick)
+                    .in_set(FixedSet::Simulate)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(OnExit(GameState::Running), clear_pending_jumps)
+            .add_systems(
+                Update,
+                (update_controller_indicator, send_game_events).after(sync_controller_status),
+            );
+    }
+}
+
+/// Everything the listener thread shares with the game.
+#[derive(Clone)]
+struct SerialLink {
+    inbox: Sender<ReceivedMessage>,
+    outbox: Arc<Mutex<Vec<GameMessage>>>,
+    status: Arc<Mutex<ControllerStatus>>,
+    stats: Arc<Mutex<SerialStats>>,
+}
+
+pub fn setup_serial_listener(
+    inbox: Res<ControllerInbox>,
+    outbox: Res<ControllerOutbox>,
+    status: Res<SharedControllerStatus>,
+    stats: Res<SharedSerialStats>,
+    settings: Res<SerialSettings>,
+) {
+    let link = SerialLink {
+        inbox: inbox.sender.clone(),
+        outbox: outbox.0.clone(),
+        status: status.0.clone(),
+        stats: stats.0.clone(),
+    };
+    let settings = settings.clone();
+
+    thread::spawn(move || run_serial_manager(settings, link));
+}
+
+/// Finds and opens the controller, talks to it until it goes away, then tries again
+/// with exponential backoff. Scanned ports that turn out not to be a controller are
+/// let go of, and the next one is tried.
+fn run_serial_manager(settings: SerialSettings, link: SerialLink) {
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        for name in settings.candidates() {
+            let port = match serialport::new(&name, settings.baud).timeout(POLL_INTERVAL).open() {
+                Ok(port) => port,
+                // Only report the first failure of a streak, not every retry
+                Err(err) => {
+                    if backoff == MIN_BACKOFF {
+                        eprintln!("⚠️ Kunne ikke åbne {name}: {err}");
+                    }
+                    continue;
+                }
+            };
+
+            let result = talk(port, &name, &link, settings.picks_controller());
+            let connected = link
+                .status
+                .lock()
+                .is_ok_and(|status| *status != ControllerStatus::Disconnected);
+            if !connected {
+                if backoff == MIN_BACKOFF {
+                    let reason = result.err().map_or_else(|| "closed".to_string(), |err| err.to_string());
+                    println!("🔌 Skipping {name}, no controller there ({reason})");
+                }
+                continue;
+            }
+
+            if let Err(err) = result {
+                eprintln!("⚠️ {name}: {err}");
+            }
+            println!("🔌 Controller on {name} disconnected");
+            set_status(&link.status, ControllerStatus::Disconnected);
+            // Start over from the first port, it may well have come back there
+            backoff = MIN_BACKOFF;
+            break;
+        }
+
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Marks `name` as the controller's port, from a clean outbox.
+fn connect(name: &str, link: &SerialLink) {
+    println!("🔌 Controller connected on {name}");
+    // Whatever queued up before is stale; the game resends its state on connect
+    if let Ok(mut outbox) = link.outbox.lock() {
+        outbox.clear();
+    }
+    set_status(&link.status, ControllerStatus::Connected { port: name.to_string(), device: None });
+}
+
+/// Reads lines from the controller, answers handshakes and pings, and writes out the
+/// outbox. Returns when the port closes, fails with anything but a read timeout, or a
+/// handshaken controller goes silent.
+///
+/// Unless `identified`, the port has `IDENTIFY_TIMEOUT` to send `HELLO` or another
+/// valid line. Until it does, nothing is written to it and the game isn't told about it.
+fn talk(port: Box<dyn SerialPort>, name: &str, link: &SerialLink, mut identified: bool) -> std::io::Result<()> {
+    let mut writer = port.try_clone()?;
+    let mut reader = BufReader::new(port);
+    let mut line = String::new();
+    let mut handshaken = false;
+    let opened_at = Instant::now();
+    let mut last_heard = opened_at;
+    let mut pinged = false;
+
+    if identified {
+        connect(name, link);
+    }
+
+    loop {
+        if !identified && opened_at.elapsed() > IDENTIFY_TIMEOUT {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "no HELLO or valid line",
+            ));
+        }
+
+        // The outbox stays shut until the port has proven to be a controller
+        let outgoing = match link.outbox.lock() {
+            Ok(mut outbox) if identified => std::mem::take(&mut *outbox),
+            _ => Vec::new(),
+        };
+        for message in outgoing {
+            writeln!(writer, "{message}")?;
+        }
+
+        // A timeout can land mid-line; keep the partial line until its newline arrives
+        match reader.read_line(&mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) if !line.ends_with('\n') => continue,
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {
+                // Only controllers that did the handshake are known to answer PING
+                if handshaken {
+                    let silence = last_heard.elapsed();
+                    if silence > SILENCE_TIMEOUT {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            "controller stopped answering PING",
+                        ));
+                    }
+                    if silence > PING_AFTER && !pinged {
+                        writeln!(writer, "PING")?;
+                        pinged = true;
+                    }
+                }
+                continue;
+            }
+            Err(err) => return Err(err),
+        }
+
+        let parsed = parse_line(&line);
+        line.clear();
+        // A line this game understands is what proves the port; anything else there isn't ours to count
+        if !identified {
+            match &parsed {
+                Ok(ControllerMessage::Hello { version, .. }) if *version != PROTOCOL_VERSION => {}
+                Ok(_) => {
+                    connect(name, link);
+                    identified = true;
+                }
+                Err(_) => continue,
+            }
+        }
+
+        last_heard = Instant::now();
+        pinged = false;
+        if let Ok(mut stats) = link.stats.lock() {
+            stats.lines += 1;
+        }
+
+        let message = match parsed {
+            Ok(message) => message,
+            Err(err) => {
+                let malformed = link.stats.lock().map(|mut stats| {
+                    stats.malformed += 1;
+                    stats.malformed
+                });
+                eprintln!("⚠️ {name}: ignored line ({err}), {} malformed so far", malformed.unwrap_or(0));
+                continue;
+            }
+        };
+
+        match message {
+            ControllerMessage::Hello { version, device_id } => {
+                if version != PROTOCOL_VERSION {
+                    eprintln!("⚠️ {name}: {device_id} speaks protocol {version}, expected {PROTOCOL_VERSION}");
+                    writeln!(writer, "ERROR unsupported-version {PROTOCOL_VERSION}")?;
+                    continue;
+                }
+                writeln!(writer, "HELLO {PROTOCOL_VERSION} {GAME_ID}")?;
+                println!("🤝 {device_id} on {name} (protocol {version})");
+                set_status(
+                    &link.status,
+                    ControllerStatus::Connected { port: name.to_string(), device: Some(device_id) },
+                );
+                handshaken = true;
+            }
+            ControllerMessage::Ping => writeln!(writer, "PONG")?,
+            ControllerMessage::Pong => {}
+            message => {
+                let received = ReceivedMessage { message, received_at: last_heard };
+                // The game only goes away on exit
+                let _ = link.inbox.send(received);
+            }
+        }
+    }
+}
+
+fn set_status(status: &Arc<Mutex<ControllerStatus>>, value: ControllerStatus) {
+    if let Ok(mut lock) = status.lock() {
+        *lock = value;
+    }
+}
+
+/// Drains the inbox every frame. Jumps are queued for the fixed ticks; gameplay
+/// commands outside a run are dropped, so a press made in a menu doesn't carry over
+/// as a jump once the run starts.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_controller_messages(
+    mut commands: Commands,
+    inbox: Res<ControllerInbox>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut pending: ResMut<PendingJumps>,
+    mut actions: ResMut<ActionState>,
+    mut analog: ResMut<AnalogInputs>,
+    analog_settings: Res<AnalogSettings>,
+) {
+    let Ok(receiver) = inbox.receiver.lock() else { return };
+
+    let state = *state.get();
+    for ReceivedMessage { message, received_at } in receiver.try_iter() {
+        match message {
+            ControllerMessage::Jump | ControllerMessage::Flip | ControllerMessage::Press => {
+                if message == ControllerMessage::Press {
+                    actions.hold(Action::Jump, true);
+                }
+                if state == GameState::Running {
+                    pending.0.push_back(received_at);
+                }
+            }
+            ControllerMessage::Release => actions.hold(Action::Jump, false),
+            ControllerMessage::Pause => actions.press(Action::Pause),
+            ControllerMessage::Start => match state {
+                GameState::Menu | GameState::Paused => next_state.set(GameState::Countdown),
+                GameState::GameOver => {
+                    commands.remove_resource::<ActiveRun>();
+                    next_state.set(GameState::Countdown);
+                }
+                _ => {}
+            },
+            ControllerMessage::MenuUp => actions.press(Action::NavigateUp),
+            ControllerMessage::MenuDown => actions.press(Action::NavigateDown),
+            ControllerMessage::Select => actions.press(Action::Confirm),
+            ControllerMessage::Analog { channel, value } => {
+                analog.update(channel, value, &analog_settings)
+            }
+            ControllerMessage::Hello { .. } | ControllerMessage::Ping | ControllerMessage::Pong => {}
+        }
+    }
+}
+
+/// Hands the oldest queued controller jump to this tick, timing how long it waited.
+pub fn feed_controller_jumps(
+    mut pending: ResMut<PendingJumps>,
+    mut jump: ResMut<JumpRequest>,
+    mut latency: ResMut<InputLatency>,
+) {
+    // A keyboard jump already claimed this tick; the controller's waits for the next
+    if jump.0 {
+        return;
+    }
+    if let Some(received_at) = pending.0.pop_front() {
+        jump.0 = true;
+        latency.record(received_at.elapsed());
+    }
+}
+
+pub fn clear_pending_jumps(mut pending: ResMut<PendingJumps>) {
+    pending.0.clear();
+}
+
+#[derive(Default)]
+pub struct ScoreThrottle {
+    latest: Option<u32>,
+    sent: Option<u32>,
+    sent_at: Duration,
+}
+
+/// Queues gameplay events and state changes for the controller while it is connected.
+/// `SCORE` is limited to `score_rate` lines per second and only sent when it changed.
+pub fn send_game_events(
+    mut events: EventReader<GameEvent>,
+    state: Res<State<GameState>>,
+    status: Res<ControllerStatus>,
+    settings: Res<SerialSettings>,
+    outbox: Res<ControllerOutbox>,
+    time: Res<Time<Real>>,
+    mut score: Local<ScoreThrottle>,
+) {
+    if *status == ControllerStatus::Disconnected {
+        events.clear();
+        return;
+    }
+
+    let mut messages = Vec::new();
+    for event in events.read() {
+        match *event {
+            GameEvent::ScoreChanged(value) => score.latest = Some(value),
+            GameEvent::CoinCollected { total } => messages.push(GameMessage::Coin(total)),
+            GameEvent::PlayerDied => messages.push(GameMessage::Death),
+            GameEvent::Flipped { to_ceiling } => messages.push(GameMessage::Flip { up: to_ceiling }),
+        }
+    }
+
+    // A fresh connection starts with a clean outbox, so tell it where we are
+    if state.is_changed() || status.is_changed() {
+        messages.push(GameMessage::State(*state.get()));
+    }
+
+    // The latest score is kept until there's room for it, so the final one isn't lost
+    let now = time.elapsed();
+    let min_gap = Duration::from_secs_f32(1.0 / settings.score_rate.max(0.01));
+    if let Some(value) = score.latest {
+        let due = score.sent.is_none() || now.saturating_sub(score.sent_at) >= min_gap;
+        if score.sent != Some(value) && due {
+            messages.push(GameMessage::Score(value));
+            score.sent = Some(value);
+            score.sent_at = now;
+        }
+    }
+
+    if messages.is_empty() {
+        return;
+    }
+    if let Ok(mut outbox) = outbox.0.lock() {
+        outbox.extend(messages);
+    }
+}
+
+pub fn sync_controller_status(
+    shared: Res<SharedControllerStatus>,
+    mut status: ResMut<ControllerStatus>,
+) {
+    let Ok(current) = shared.0.lock() else { return };
+    if *status != *current {
+        *status = current.clone();
+    }
+}
+
+pub fn spawn_controller_indicator(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+        )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(8.0),
+                left: Val::Px(8.0),
+                ..default()
+            }),
+        ControllerIndicator,
+    ));
+}
+
+pub fn update_controller_indicator(
+    status: Res<ControllerStatus>,
+    latency: Res<InputLatency>,
+    mut query: Query<&mut Text, With<ControllerIndicator>>,
+) {
+    if !status.is_changed() && !latency.is_changed() {
+        return;
+    }
+
+    let (mut label, color) = match &*status {
+        ControllerStatus::Connected { port, device: Some(device) } => {
+            (format!("Controller connected: {device} ({port})"), Color::GREEN)
+        }
+        ControllerStatus::Connected { port, device: None } => {
+            (format!("Controller connected ({port})"), Color::GREEN)
+        }
+        ControllerStatus::Disconnected => ("Controller disconnected".to_string(), Color::RED),
+    };
+    if let (ControllerStatus::Connected { .. }, Some(last), Some(average)) =
+        (&*status, latency.last, latency.average)
+    {
+        label += &format!(
+            " - input latency {:.1} ms (avg {:.1} ms)",
+            last.as_secs_f64() * 1000.0,
+            average.as_secs_f64() * 1000.0
+        );
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = label.clone();
+        text.sections[0].style.color = color;
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use crate::resources::GameState;
+
+/// Version of the line protocol spoken with controllers. A controller announces
+/// its version with `HELLO <version> <device-id>` and the game answers with its own.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Name the game introduces itself with in its `HELLO` reply.
+pub const GAME_ID: &str = "rust_spil";
+
+/// Largest raw `ANALOG` reading either side of zero. Any 16-bit ADC fits; a reading
+/// beyond it is a garbled line, not a sensor.
+pub const ANALOG_LIMIT: i32 = 65535;
+
+/// One newline-terminated line sent by a controller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControllerMessage {
+    Hello { version: u32, device_id: String },
+    Jump,
+    Flip,
+    /// Button went down; paired with `Release` for hold mechanics.
+    Press,
+    Release,
+    Pause,
+    Start,
+    MenuUp,
+    MenuDown,
+    Select,
+    /// Raw reading from an analog sensor; see `analog` for calibration.
+    Analog { channel: u8, value: i32 },
+    Ping,
+    Pong,
+}
+
+/// One line the game sends to the controller, besides handshake and keepalive replies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameMessage {
+    State(GameState),
+    Score(u32),
+    Coin(u32),
+    Death,
+    Flip { up: bool },
+}
+
+impl fmt::Display for GameMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameMessage::State(state) => {
+                let name = match state {
+                    GameState::Menu => "MENU",
+                    GameState::Countdown => "COUNTDOWN",
+                    GameState::Running => "RUNNING",
+                    GameState::Paused => "PAUSED",
+                    GameState::GameOver => "GAMEOVER",
+                };
+                write!(f, "STATE {name}")
+            }
+            GameMessage::Score(score) => write!(f, "SCORE {score}"),
+            GameMessage::Coin(total) => write!(f, "COIN {total}"),
+            GameMessage::Death => write!(f, "DEATH"),
+            GameMessage::Flip { up } => write!(f, "FLIP {}", if *up { "up" } else { "down" }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    UnknownCommand(String),
+    BadArguments(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty line"),
+            ParseError::UnknownCommand(command) => write!(f, "unknown command {command:?}"),
+            ParseError::BadArguments(line) => write!(f, "bad arguments in {line:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses one line. Commands are case-sensitive; surrounding whitespace is ignored.
+pub fn parse_line(line: &str) -> Result<ControllerMessage, ParseError> {
+    let mut parts = line.split_whitespace();
+    let command = parts.next().ok_or(ParseError::Empty)?;
+    let args: Vec<&str> = parts.collect();
+
+    let no_args = |message: ControllerMessage| {
+        if args.is_empty() {
+            Ok(message)
+        } else {
+            Err(ParseError::BadArguments(line.trim().to_string()))
+        }
+    };
+
+    match command {
+        "HELLO" => match args.as_slice() {
+            [version, device_id] => {
+                let version = version
+                    .parse()
+                    .map_err(|_| ParseError::BadArguments(line.trim().to_string()))?;
+                Ok(ControllerMessage::Hello {
+                    version,
+                    device_id: device_id.to_string(),
+                })
+            }
+            _ => Err(ParseError::BadArguments(line.trim().to_string())),
+        },
+        "ANALOG" => match args.as_slice() {
+            [channel, value] => match (channel.parse(), value.parse::<i32>()) {
+                (Ok(channel), Ok(value)) if (-ANALOG_LIMIT..=ANALOG_LIMIT).contains(&value) => {
+                    Ok(ControllerMessage::Analog { channel, value })
+                }
+                _ => Err(ParseError::BadArguments(line.trim().to_string())),
+            },
+            _ => Err(ParseError::BadArguments(line.trim().to_string())),
+        },
+        "JUMP" => no_args(ControllerMessage::Jump),
+        "FLIP" => no_args(ControllerMessage::Flip),
+        "PRESS" => no_args(ControllerMessage::Press),
+        "RELEASE" => no_args(ControllerMessage::Release),
+        "PAUSE" => no_args(ControllerMessage::Pause),
+        "START" => no_args(ControllerMessage::Start),
+        "MENU_UP" => no_args(ControllerMessage::MenuUp),
+        "MENU_DOWN" => no_args(ControllerMessage::MenuDown),
+        "SELECT" => no_args(ControllerMessage::Select),
+        "PING" => no_args(ControllerMessage::Ping),
+        "PONG" => no_args(ControllerMessage::Pong),
+        other => Err(ParseError::UnknownCommand(other.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bad(line: &str) -> Result<ControllerMessage, ParseError> {
+        Err(ParseError::BadArguments(line.trim().to_string()))
+    }
+
+    #[test]
+    fn hello_needs_a_numeric_version_and_a_device() {
+        assert_eq!(
+            parse_line("HELLO 1 pad-7\n"),
+            Ok(ControllerMessage::Hello { version: 1, device_id: "pad-7".to_string() })
+        );
+        // Another version still parses; the listener is the one to turn it away
+        assert_eq!(
+            parse_line("HELLO 2 pad-7"),
+            Ok(ControllerMessage::Hello { version: 2, device_id: "pad-7".to_string() })
+        );
+        assert_eq!(parse_line("HELLO one pad-7"), bad("HELLO one pad-7"));
+        assert_eq!(parse_line("HELLO -1 pad-7"), bad("HELLO -1 pad-7"));
+        assert_eq!(parse_line("HELLO 1"), bad("HELLO 1"));
+        assert_eq!(parse_line("HELLO 1 pad 7"), bad("HELLO 1 pad 7"));
+    }
+
+    #[test]
+    fn commands_without_arguments_take_none() {
+        assert_eq!(parse_line("  JUMP \r\n"), Ok(ControllerMessage::Jump));
+        assert_eq!(parse_line("MENU_DOWN"), Ok(ControllerMessage::MenuDown));
+        assert_eq!(parse_line("PING"), Ok(ControllerMessage::Ping));
+        assert_eq!(parse_line("JUMP 1"), bad("JUMP 1"));
+        assert_eq!(parse_line("PONG now\n"), bad("PONG now"));
+    }
+
+    #[test]
+    fn analog_needs_a_channel_and_a_value() {
+        assert_eq!(parse_line("ANALOG 1 512"), Ok(ControllerMessage::Analog { channel: 1, value: 512 }));
+        assert_eq!(parse_line("ANALOG 1"), bad("ANALOG 1"));
+        assert_eq!(parse_line("ANALOG 1 512 3"), bad("ANALOG 1 512 3"));
+        assert_eq!(parse_line("ANALOG x 512"), bad("ANALOG x 512"));
+        assert_eq!(parse_line("ANALOG 256 512"), bad("ANALOG 256 512"));
+        assert_eq!(parse_line("ANALOG 1 5.5"), bad("ANALOG 1 5.5"));
+        assert_eq!(parse_line("ANALOG 0 -65535"), Ok(ControllerMessage::Analog { channel: 0, value: -65535 }));
+        assert_eq!(parse_line("ANALOG 0 65536"), bad("ANALOG 0 65536"));
+        assert_eq!(parse_line("ANALOG 0 -2147483648"), bad("ANALOG 0 -2147483648"));
+    }
+
+    #[test]
+    fn unknown_and_empty_lines_are_errors() {
+        assert_eq!(parse_line("   \n"), Err(ParseError::Empty));
+        assert_eq!(parse_line("jump"), Err(ParseError::UnknownCommand("jump".to_string())));
+        assert_eq!(parse_line("WIGGLE 3"), Err(ParseError::UnknownCommand("WIGGLE".to_string())));
+    }
+}
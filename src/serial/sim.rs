@@ -0,0 +1,150 @@
+use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serialport::{SerialPort, TTYPort};
+
+use super::protocol::PROTOCOL_VERSION;
+use super::SerialSettings;
+
+/// A stand-in for the hardware controller: one end of a pseudo-terminal pair that
+/// the game opens as its serial port, driven from the other end. Lines the game
+/// sends back are collected, and `PING` is answered with `PONG` unless turned off.
+pub struct VirtualController {
+    port_name: String,
+    writer: Mutex<TTYPort>,
+    received: Arc<(Mutex<Vec<String>>, Condvar)>,
+    auto_pong: Arc<AtomicBool>,
+    closed: Arc<AtomicBool>,
+}
+
+impl VirtualController {
+    pub fn new() -> serialport::Result<Self> {
+        // The slave end is dropped right away: the game claims it exclusively when it
+        // opens it, and a second handle here would keep that claim alive across reconnects
+        let (master, slave) = TTYPort::pair()?;
+        let port_name = slave.name().ok_or_else(|| {
+            serialport::Error::new(serialport::ErrorKind::Unknown, "pseudo-terminal has no name")
+        })?;
+        drop(slave);
+
+        let writer = master.try_clone_native()?;
+        let mut ponger = master.try_clone_native()?;
+        let controller = Self {
+            port_name,
+            writer: Mutex::new(writer),
+            received: Arc::default(),
+            auto_pong: Arc::new(AtomicBool::new(true)),
+            closed: Arc::default(),
+        };
+
+        let reader = BufReader::new(master);
+        let received = controller.received.clone();
+        let auto_pong = controller.auto_pong.clone();
+        let closed = controller.closed.clone();
+        thread::spawn(move || {
+            read_game_lines(reader, &mut ponger, &received, &auto_pong, &closed)
+        });
+
+        Ok(controller)
+    }
+
+    /// Path the game should open, e.g. `/dev/pts/3`.
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    /// Settings that point the game at this controller. Pseudo-terminals have no
+    /// baud rate, so it is left at 0 to skip the line setup real ports get.
+    pub fn serial_settings(&self) -> SerialSettings {
+        SerialSettings {
+            port: Some(self.port_name.clone()),
+            baud: 0,
+            ..SerialSettings::default()
+        }
+    }
+
+    /// Sends one protocol line; the newline is added here.
+    pub fn send(&self, line: &str) -> std::io::Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{line}")?;
+        writer.flush()
+    }
+
+    /// Introduces the controller with the current protocol version.
+    pub fn hello(&self, device_id: &str) -> std::io::Result<()> {
+        self.send(&format!("HELLO {PROTOCOL_VERSION} {device_id}"))
+    }
+
+    pub fn set_auto_pong(&self, enabled: bool) {
+        self.auto_pong.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Every line received from the game so far, oldest first.
+    pub fn received(&self) -> Vec<String> {
+        self.received.0.lock().unwrap().clone()
+    }
+
+    /// Waits until the game has sent `expected` (at any point so far) or `timeout` passes.
+    pub fn wait_for_line(&self, expected: &str, timeout: Duration) -> bool {
+        self.wait_for(|line| line == expected, timeout)
+    }
+
+    /// Waits until any line received so far matches, or `timeout` passes.
+    pub fn wait_for(&self, matches: impl Fn(&str) -> bool, timeout: Duration) -> bool {
+        let (lines, arrived) = &*self.received;
+        let deadline = Instant::now() + timeout;
+        let mut lines = lines.lock().unwrap();
+        loop {
+            if lines.iter().any(|line| matches(line)) {
+                return true;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return false;
+            }
+            lines = arrived.wait_timeout(lines, left).unwrap().0;
+        }
+    }
+}
+
+impl Drop for VirtualController {
+    fn drop(&mut self) {
+        // The reader thread notices on its next timeout and closes the master end,
+        // which the game sees as the controller being unplugged
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+fn read_game_lines(
+    mut reader: BufReader<TTYPort>,
+    ponger: &mut TTYPort,
+    received: &(Mutex<Vec<String>>, Condvar),
+    auto_pong: &AtomicBool,
+    closed: &AtomicBool,
+) {
+    let mut line = String::new();
+    while !closed.load(Ordering::Relaxed) {
+        match reader.read_line(&mut line) {
+            Ok(_) if line.ends_with('\n') => {}
+            Err(err) if err.kind() == std::io::ErrorKind::TimedOut => continue,
+            // Nobody has the other end open (yet, or any more)
+            Ok(_) | Err(_) => {
+                thread::sleep(Duration::from_millis(50));
+                continue;
+            }
+        }
+
+        let text = line.trim().to_string();
+        line.clear();
+        if text == "PING" && auto_pong.load(Ordering::Relaxed) {
+            let _ = writeln!(ponger, "PONG");
+        }
+
+        let (lines, arrived) = received;
+        lines.lock().unwrap().push(text);
+        arrived.notify_all();
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+const IDLE_COLOR: Color = Color::GRAY;
+const HOVERED_COLOR: Color = Color::DARK_GRAY;
+
+/// Spawns a button tagged with `marker`, so its handler can tell which one was
+/// pressed, with `label` as its text. The label may carry a tag of its own to
+/// update the text later.
+pub fn spawn_button(parent: &mut ChildBuilder, style: Style, marker: impl Component, label: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style,
+                background_color: IDLE_COLOR.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(label);
+        });
+}
+
+type ChangedButton<M> = (Changed<Interaction>, With<M>);
+
+/// Darkens the buttons tagged with `M` while the cursor is over them.
+pub fn highlight_buttons<M: Component>(mut query: Query<(&Interaction, &mut BackgroundColor), ChangedButton<M>>) {
+    for (interaction, mut color) in query.iter_mut() {
+        match *interaction {
+            Interaction::Hovered => *color = HOVERED_COLOR.into(),
+            Interaction::None => *color = IDLE_COLOR.into(),
+            Interaction::Pressed => {}
+        }
+    }
+}
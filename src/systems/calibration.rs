@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::serial::analog::{AnalogInputs, AnalogMapping, AnalogSettings};
+use crate::systems::countdown::CountdownSettings;
+use crate::systems::menu::{spawn_menu_button, MenuButton};
+
+#[derive(Component)]
+pub struct CalibrationReadout;
+
+/// Text of the button that starts and finishes range capture.
+#[derive(Component)]
+pub struct CaptureLabel;
+
+pub fn capture_label(inputs: &AnalogInputs) -> &'static str {
+    if inputs.capture.is_some() {
+        "Finish calibration"
+    } else {
+        "Calibrate range"
+    }
+}
+
+/// Fills the menu page with live analog readings and the range calibration controls.
+pub fn spawn_calibration_page(page: &mut ChildBuilder, text_style: &TextStyle) {
+    page.spawn(TextBundle::from_section(
+        "Controller",
+        TextStyle { font_size: 50.0, ..text_style.clone() },
+    ));
+    page.spawn((
+        TextBundle::from_section(
+            "No analog input yet",
+            TextStyle { font_size: 22.0, ..text_style.clone() },
+        )
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            }),
+        CalibrationReadout,
+    ));
+    spawn_menu_button(page, "Calibrate range", MenuButton::Calibrate, text_style);
+    spawn_menu_button(page, "Save", MenuButton::SaveCalibration, text_style);
+    spawn_menu_button(page, "Back", MenuButton::Back, text_style);
+}
+
+/// Text bar for a 0..=1 value, e.g. `[######----]`.
+fn bar(value: f32) -> String {
+    let filled = (value.clamp(0.0, 1.0) * 20.0).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(20 - filled))
+}
+
+pub fn update_calibration_readout(
+    inputs: Res<AnalogInputs>,
+    settings: Res<AnalogSettings>,
+    mut query: Query<&mut Text, With<CalibrationReadout>>,
+) {
+    if !inputs.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let mut lines = Vec::new();
+    for (channel, reading) in &inputs.readings {
+        let calibration = settings.channels.get(channel).cloned().unwrap_or_default();
+        let target = match calibration.mapping {
+            AnalogMapping::Unused => "unused".to_string(),
+            AnalogMapping::JumpStrength { min_scale, max_scale } => {
+                format!("jump x{:.2}", min_scale + (max_scale - min_scale) * reading.filtered)
+            }
+            AnalogMapping::FlipSpeed { min_scale, max_scale } => {
+                format!("flip speed x{:.2}", min_scale + (max_scale - min_scale) * reading.filtered)
+            }
+            AnalogMapping::MenuSlider => {
+                format!("countdown {:.0} s", CountdownSettings::from_slider(reading.filtered).seconds)
+            }
+        };
+        let range = match inputs.capture.as_ref().and_then(|capture| capture.get(channel)) {
+            Some((min, max)) => format!("seen {min}..{max}"),
+            None => format!("range {}..{}", calibration.min, calibration.max),
+        };
+        lines.push(format!(
+            "Ch {channel}: raw {:>5}  {range}  {} {:.2}  -> {target}",
+            reading.raw,
+            bar(reading.filtered),
+            reading.filtered,
+        ));
+    }
+
+    let text = if lines.is_empty() {
+        "No analog input yet".to_string()
+    } else {
+        lines.join("\n")
+    };
+    for mut readout in query.iter_mut() {
+        readout.sections[0].value = text.clone();
+    }
+}
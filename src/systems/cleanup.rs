@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use crate::components::{DespawnOnExit, RunScoped};
+use crate::resources::{ActiveRun, GameState};
+
+/// Builds an `OnExit(state)` system that despawns everything tagged `DespawnOnExit(state)`.
+pub fn despawn_on_exit(
+    state: GameState,
+) -> impl FnMut(Commands, Query<(Entity, &DespawnOnExit)>) {
+    move |mut commands, query| {
+        for (entity, scope) in query.iter() {
+            if scope.0 == state {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn despawn_run_entities(mut commands: Commands, query: Query<Entity, With<RunScoped>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Marks the current run as finished, so entering `Running` again starts a new one.
+pub fn end_run(mut commands: Commands) {
+    commands.remove_resource::<ActiveRun>();
+}
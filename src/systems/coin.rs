@@ -2,9 +2,12 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::components::{Coin, Player};
-use crate::resources::{Score, CoinWallet};
-use crate::constants::OBSTACLE_SPEED;
+use crate::components::{Coin, Pickup, RunScoped};
+use crate::resources::{Score, CoinWallet, GameAssets, GameRng};
+use crate::config::GameConfig;
+use crate::events::{GameEvent, PlayerTouchedPickup};
+use crate::hitbox::Hitbox;
+use crate::systems::interpolation::Interpolated;
 
 #[derive(Resource)]
 pub struct CoinSpawnTimer(pub Timer);
@@ -12,12 +15,14 @@ pub struct CoinSpawnTimer(pub Timer);
 /// Spawns coins in the middle of the screen after score 100
 pub fn spawn_coins(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     score: Res<Score>,
     time: Res<Time>,
     mut timer: ResMut<CoinSpawnTimer>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
 ) {
-    if score.0 < 100.0 {
+    if score.0 < config.flip_score_threshold {
         return;
     }
 
@@ -26,16 +31,14 @@ pub fn spawn_coins(
         return;
     }
 
-    let mut rng = rand::thread_rng();
-    let x = rng.gen_range(300.0..500.0);
-    let y = 0.0;
+    let translation = Vec3::new(rng.random_range(300.0..500.0), 0.0, 0.0);
 
-    let texture = asset_server.load("coin.png");
+    let texture = assets.coin.clone();
 
     commands.spawn((
         SpriteBundle {
             texture,
-            transform: Transform::from_xyz(x, y, 0.0),
+            transform: Transform::from_translation(translation),
             sprite: Sprite {
                 custom_size: Some(Vec2::splat(60.0)),
                 ..default()
@@ -43,6 +46,10 @@ pub fn spawn_coins(
             ..default()
         },
         Coin,
+        Pickup,
+        Hitbox::new(Vec2::splat(config.coin_hitbox_size)),
+        Interpolated::at(translation),
+        RunScoped,
     ));
 }
 
@@ -50,14 +57,15 @@ pub fn spawn_coins(
 pub fn move_coins(
     mut commands: Commands,
     time: Res<Time>,
+    config: Res<GameConfig>,
     mut query: Query<(Entity, &mut Transform), With<Coin>>,
 ) {
     let delta_time = time.delta_seconds();
 
     for (entity, mut transform) in query.iter_mut() {
-        transform.translation.x += OBSTACLE_SPEED * delta_time;
+        transform.translation.x += config.obstacle_speed * delta_time;
 
-        if transform.translation.x < -400.0 {
+        if transform.translation.x < config.despawn_x {
             commands.entity(entity).despawn();
         }
     }
@@ -67,17 +75,16 @@ pub fn move_coins(
 pub fn collect_coins(
     mut commands: Commands,
     mut wallet: ResMut<CoinWallet>,
-    player_query: Query<&Transform, With<Player>>,
-    coin_query: Query<(Entity, &Transform), With<Coin>>,
+    mut touches: EventReader<PlayerTouchedPickup>,
+    coin_query: Query<(), With<Coin>>,
+    mut events: EventWriter<GameEvent>,
 ) {
-    if let Ok(player_transform) = player_query.get_single() {
-        for (coin_entity, coin_transform) in coin_query.iter() {
-            let distance = player_transform.translation.distance(coin_transform.translation);
-            if distance < 30.0 {
-                wallet.coins += 1;
-                commands.entity(coin_entity).despawn();
-                println!("\u{1F4B0} Coin collected! Total: {}", wallet.coins);
-            }
+    for touch in touches.read() {
+        if coin_query.contains(touch.pickup) {
+            wallet.coins += 1;
+            commands.entity(touch.pickup).despawn();
+            events.send(GameEvent::CoinCollected { total: wallet.coins });
+            println!("\u{1F4B0} Coin collected! Total: {}", wallet.coins);
         }
     }
 }
@@ -1,51 +1,72 @@
 use bevy::prelude::*;
-use bevy::ecs::schedule::NextState;
-
-use crate::components::{Player, Obstacle};
-use crate::resources::{Score, GameState};
-use crate::constants::{PLAYER_SIZE, OBSTACLE_SIZE};
-
-pub fn check_collisions(
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<GameState>>,
-    score: ResMut<Score>,
-    player_query: Query<(&Transform, &Player), With<Player>>,
-    obstacle_query: Query<(Entity, &Transform), With<Obstacle>>,
-    asset_server: Res<AssetServer>,
+
+use crate::components::{Hazard, Pickup, Player};
+use crate::events::{Contact, PlayerHitHazard, PlayerTouchedPickup};
+use crate::hitbox::{sweep, Hitbox};
+use crate::systems::interpolation::Interpolated;
+
+/// Everything with a hitbox that `F` picks out, e.g. `With<Hazard>`.
+type HitboxQuery<'w, 's, F> = Query<
+    'w,
+    's,
+    (Entity, &'static Transform, Option<&'static Interpolated>, &'static Hitbox, Option<&'static Sprite>),
+    F,
+>;
+
+/// A hitbox's outline where it started the tick, and how far it moved since.
+struct Swept {
+    start_shape: Vec<Vec2>,
+    start: Vec2,
+    motion: Vec2,
+}
+
+impl Swept {
+    fn new(transform: &Transform, interpolated: Option<&Interpolated>, hitbox: &Hitbox, sprite: Option<&Sprite>) -> Self {
+        let start = interpolated.map_or(transform.translation, |interpolated| interpolated.previous).truncate();
+        let motion = transform.translation.truncate() - start;
+        let start_shape = hitbox
+            .world_points(transform, sprite)
+            .into_iter()
+            .map(|point| point - motion)
+            .collect();
+        Self { start_shape, start, motion }
+    }
+
+    /// Where this first touched `other` during the tick, both moving at once.
+    fn contact(&self, other: &Swept) -> Option<Contact> {
+        let hit = sweep(&self.start_shape, self.motion - other.motion, &other.start_shape)?;
+        Some(Contact {
+            time: hit.time,
+            normal: hit.normal,
+            position: self.start + self.motion * hit.time,
+        })
+    }
+}
+
+/// Finds everything the player's hitbox touched this tick and reports it as
+/// `PlayerHitHazard` and `PlayerTouchedPickup` events, for other systems to act on.
+///
+/// Hitboxes are swept from where they started the tick to where they are now, so a
+/// long tick can't carry the player through a spike or past a coin.
+pub fn detect_collisions(
+    player_query: HitboxQuery<With<Player>>,
+    hazard_query: HitboxQuery<With<Hazard>>,
+    pickup_query: HitboxQuery<With<Pickup>>,
+    mut hazard_events: EventWriter<PlayerHitHazard>,
+    mut pickup_events: EventWriter<PlayerTouchedPickup>,
 ) {
-    if let Ok((player_transform, _)) = player_query.get_single() {
-        for (_, obstacle_transform) in obstacle_query.iter() {
-            let collision = player_transform.translation.x < obstacle_transform.translation.x + OBSTACLE_SIZE.x
-                && player_transform.translation.x + PLAYER_SIZE.x > obstacle_transform.translation.x
-                && player_transform.translation.y < obstacle_transform.translation.y + OBSTACLE_SIZE.y
-                && player_transform.translation.y + PLAYER_SIZE.y > obstacle_transform.translation.y;
-
-            if collision {
-                println!("💥 Game Over! Final Score: {:.0}", score.0);
-                next_state.set(GameState::GameOver);
-
-                for (obstacle_entity, _) in obstacle_query.iter() {
-                    commands.entity(obstacle_entity).despawn();
-                }
-
-                commands.spawn(
-                    TextBundle::from_section(
-                        format!("Game Over!\nScore: {:.0}\nPress R to Restart", score.0),
-                        TextStyle {
-                            font: asset_server.load("FiraSans-Bold.ttf"),
-                            font_size: 50.0,
-                            color: Color::WHITE,
-                        },
-                    )
-                        .with_style(Style {
-                            position_type: PositionType::Absolute,
-                            top: Val::Px(100.0),
-                            left: Val::Px(250.0),
-                            ..default()
-                        }),
-                );
-
-                break;
+    for (player, transform, interpolated, hitbox, sprite) in player_query.iter() {
+        let swept_player = Swept::new(transform, interpolated, hitbox, sprite);
+
+        for (hazard, transform, interpolated, hitbox, sprite) in hazard_query.iter() {
+            if let Some(contact) = swept_player.contact(&Swept::new(transform, interpolated, hitbox, sprite)) {
+                hazard_events.send(PlayerHitHazard { player, hazard, contact });
+            }
+        }
+
+        for (pickup, transform, interpolated, hitbox, sprite) in pickup_query.iter() {
+            if let Some(contact) = swept_player.contact(&Swept::new(transform, interpolated, hitbox, sprite)) {
+                pickup_events.send(PlayerTouchedPickup { player, pickup, contact });
             }
         }
     }
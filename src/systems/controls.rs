@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::input::{Action, ActionBinding, InputBindings, BINDINGS_PATH};
+use crate::systems::button::spawn_button;
+use crate::systems::menu::MenuButton;
+
+/// Action waiting for its new key or button on the Controls page.
+#[derive(Resource)]
+pub struct RebindTarget {
+    pub action: Action,
+    /// Set a frame after the rebind button was pressed, so that press isn't taken as the new binding
+    ready: bool,
+}
+
+impl RebindTarget {
+    pub fn new(action: Action) -> Self {
+        Self { action, ready: false }
+    }
+}
+
+/// Text of an action's rebind button.
+#[derive(Component)]
+pub struct BindingLabel(pub Action);
+
+fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::Jump => "Jump",
+        Action::Flip => "Flip",
+        Action::Pause => "Pause",
+        Action::Restart => "Restart",
+        Action::Confirm => "Confirm",
+        Action::Back => "Back",
+        Action::NavigateUp => "Up",
+        Action::NavigateDown => "Down",
+        Action::ToggleHitboxes => "Hitboxes",
+    }
+}
+
+pub fn binding_label(action: Action, binding: &ActionBinding) -> String {
+    let inputs: Vec<String> = binding
+        .keys
+        .iter()
+        .map(|key| format!("{key:?}"))
+        .chain(binding.mouse.iter().map(|button| format!("Mouse {button:?}")))
+        .chain(binding.gamepad.iter().map(|button| format!("Pad {button:?}")))
+        .collect();
+    let inputs = if inputs.is_empty() { "unbound".to_string() } else { inputs.join(", ") };
+    format!("{}: {inputs}", action_name(action))
+}
+
+pub fn spawn_controls_page(page: &mut ChildBuilder, text_style: &TextStyle, bindings: &InputBindings) {
+    let small = TextStyle { font_size: 22.0, ..text_style.clone() };
+
+    page.spawn(TextBundle::from_section(
+        "Controls",
+        TextStyle { font_size: 40.0, ..text_style.clone() },
+    ));
+    for action in Action::ALL {
+        let style = Style {
+            width: Val::Px(520.0),
+            height: Val::Px(34.0),
+            margin: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+        let text = TextBundle::from_section(binding_label(action, &bindings.binding(action)), small.clone());
+        spawn_button(page, style, MenuButton::Rebind(action), (text, BindingLabel(action)));
+    }
+}
+
+/// Replaces the waiting action's keyboard, mouse or gamepad binding with whatever is
+/// pressed next, and saves the result.
+pub fn capture_rebind(
+    mut commands: Commands,
+    mut target: ResMut<RebindTarget>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    if !target.ready {
+        target.ready = true;
+        return;
+    }
+
+    let key = keyboard.get_just_pressed().next().copied();
+    let mouse_button = mouse.get_just_pressed().next().copied();
+    let pad_button = gamepad_buttons.get_just_pressed().next().map(|button| button.button_type);
+    if key.is_none() && mouse_button.is_none() && pad_button.is_none() {
+        return;
+    }
+
+    let binding = bindings.actions.entry(target.action).or_default();
+    if let Some(key) = key {
+        binding.keys = vec![key];
+    } else if let Some(button) = mouse_button {
+        binding.mouse = vec![button];
+    } else if let Some(button) = pad_button {
+        binding.gamepad = vec![button];
+    }
+    commands.remove_resource::<RebindTarget>();
+
+    match bindings.save(Path::new(BINDINGS_PATH)) {
+        Ok(()) => println!("🎮 Bindings saved to {BINDINGS_PATH}"),
+        Err(err) => eprintln!("⚠️ {err}"),
+    }
+}
+
+pub fn update_binding_labels(
+    bindings: Res<InputBindings>,
+    target: Option<Res<RebindTarget>>,
+    mut labels: Query<(&mut Text, &BindingLabel)>,
+) {
+    for (mut text, label) in labels.iter_mut() {
+        let value = match &target {
+            Some(target) if target.action == label.0 => {
+                format!("{}: press a key or button...", action_name(label.0))
+            }
+            _ => binding_label(label.0, &bindings.binding(label.0)),
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+pub fn cancel_rebind(mut commands: Commands) {
+    commands.remove_resource::<RebindTarget>();
+}
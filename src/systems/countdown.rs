@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use crate::components::DespawnOnExit;
+use crate::resources::{GameAssets, GameState};
+
+/// How long the "3, 2, 1" countdown before every start or resume lasts.
+#[derive(Resource)]
+pub struct CountdownSettings {
+    pub seconds: f32,
+}
+
+impl Default for CountdownSettings {
+    fn default() -> Self {
+        Self { seconds: 3.0 }
+    }
+}
+
+impl CountdownSettings {
+    /// Longest countdown the menu slider goes up to.
+    pub const MAX_SECONDS: f32 = 5.0;
+
+    /// Whole seconds for a slider at `position`, 0..=1.
+    pub fn from_slider(position: f32) -> Self {
+        Self { seconds: (position.clamp(0.0, 1.0) * Self::MAX_SECONDS).round() }
+    }
+}
+
+#[derive(Resource)]
+pub struct CountdownTimer(pub Timer);
+
+#[derive(Component)]
+pub struct CountdownText;
+
+/// "Go!" shown for a moment once the run is actually moving.
+#[derive(Component)]
+pub struct GoBanner(pub Timer);
+
+fn countdown_style(assets: &GameAssets) -> (TextStyle, Style) {
+    (
+        TextStyle {
+            font: assets.font.clone(),
+            font_size: 120.0,
+            color: Color::WHITE,
+        },
+        Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(35.0),
+            left: Val::Percent(45.0),
+            ..default()
+        },
+    )
+}
+
+pub fn start_countdown(
+    mut commands: Commands,
+    settings: Res<CountdownSettings>,
+    assets: Res<GameAssets>,
+) {
+    commands.insert_resource(CountdownTimer(Timer::from_seconds(settings.seconds, TimerMode::Once)));
+
+    let (text_style, style) = countdown_style(&assets);
+    commands.spawn((
+        TextBundle::from_section(format!("{:.0}", settings.seconds.ceil()), text_style).with_style(style),
+        CountdownText,
+        DespawnOnExit(GameState::Countdown),
+    ));
+}
+
+/// The world is visible but frozen: gameplay only ticks in `Running`.
+pub fn tick_countdown(
+    time: Res<Time>,
+    mut timer: ResMut<CountdownTimer>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut text_query: Query<&mut Text, With<CountdownText>>,
+) {
+    timer.0.tick(time.delta());
+
+    if timer.0.finished() {
+        next_state.set(GameState::Running);
+        return;
+    }
+
+    let remaining = timer.0.remaining_secs().ceil();
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("{remaining:.0}");
+    }
+}
+
+pub fn spawn_go_banner(mut commands: Commands, assets: Res<GameAssets>) {
+    let (text_style, style) = countdown_style(&assets);
+    commands.spawn((
+        TextBundle::from_section("Go!", text_style).with_style(style),
+        GoBanner(Timer::from_seconds(0.6, TimerMode::Once)),
+        DespawnOnExit(GameState::Running),
+    ));
+}
+
+pub fn hide_go_banner(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut GoBanner)>,
+) {
+    for (entity, mut banner) in query.iter_mut() {
+        if banner.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use bevy::ecs::schedule::NextState;
+
+use crate::components::{DespawnOnExit, Obstacle};
+use crate::events::{GameEvent, PlayerHitHazard};
+use crate::input::{Action, ActionState};
+use crate::resources::{GameAssets, GameRng, GameState, Score};
+
+/// Ends the run when the player hits a hazard.
+pub fn die_on_hazard(
+    mut hits: EventReader<PlayerHitHazard>,
+    mut next_state: ResMut<NextState<GameState>>,
+    score: Res<Score>,
+    rng: Res<GameRng>,
+    mut events: EventWriter<GameEvent>,
+) {
+    // Hitting several spikes at once is still one death
+    if hits.read().last().is_none() {
+        return;
+    }
+
+    println!("💥 Game Over! Final Score: {:.0} (seed {})", score.0, rng.seed());
+    next_state.set(GameState::GameOver);
+    events.send(GameEvent::PlayerDied);
+}
+
+/// Clears the obstacles away and shows the final score.
+pub fn spawn_game_over_screen(
+    mut commands: Commands,
+    obstacle_query: Query<Entity, With<Obstacle>>,
+    score: Res<Score>,
+    rng: Res<GameRng>,
+    assets: Res<GameAssets>,
+) {
+    for obstacle_entity in obstacle_query.iter() {
+        commands.entity(obstacle_entity).despawn();
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            format!(
+                "Game Over!\nScore: {:.0}\nSeed: {}\nPress R to Restart\nEsc for the Menu",
+                score.0,
+                rng.seed()
+            ),
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 50.0,
+                color: Color::WHITE,
+            },
+        )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(100.0),
+                left: Val::Px(250.0),
+                ..default()
+            }),
+        DespawnOnExit(GameState::GameOver),
+    ));
+}
+
+/// The Back action leaves the game over screen for the main menu, where it quits.
+pub fn back_to_menu(actions: Res<ActionState>, mut next_state: ResMut<NextState<GameState>>) {
+    if actions.just_pressed(Action::Back) {
+        next_state.set(GameState::Menu);
+    }
+}
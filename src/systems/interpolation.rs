@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+
+/// Stages of one `FixedUpdate` tick. Gameplay runs in `Simulate`; the other two
+/// keep the simulated translation apart from the interpolated one that gets drawn.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FixedSet {
+    Restore,
+    Simulate,
+    Record,
+}
+
+/// Translation at the previous and the latest fixed tick, blended for rendering.
+/// While a tick simulates, `previous` is where the entity started it.
+#[derive(Component)]
+pub struct Interpolated {
+    pub previous: Vec3,
+    pub current: Vec3,
+}
+
+impl Interpolated {
+    pub fn at(translation: Vec3) -> Self {
+        Self {
+            previous: translation,
+            current: translation,
+        }
+    }
+}
+
+/// Puts the last simulated translation back before the tick runs.
+pub fn restore_translation(mut query: Query<(&mut Transform, &mut Interpolated)>) {
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = interpolated.current;
+        transform.translation = interpolated.current;
+    }
+}
+
+pub fn record_translation(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.current = transform.translation;
+    }
+}
+
+/// Draws each entity between its last two ticks by how far the clock has run past the latest one.
+pub fn interpolate_translation(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &Interpolated)>,
+) {
+    let alpha = fixed_time.overstep_percentage();
+
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
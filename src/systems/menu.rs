@@ -1,22 +1,106 @@
+use bevy::app::AppExit;
 use bevy::prelude::*;
+use crate::components::DespawnOnExit;
+use crate::input::{Action, ActionState, InputBindings, PlayerGamepad, BINDINGS_PATH};
 use crate::resources::GameState;
-use crate::components::MainCamera;
+use crate::systems::button::{highlight_buttons, spawn_button};
+use crate::serial::analog::{AnalogInputs, AnalogSettings, ANALOG_CONFIG_PATH};
+use crate::systems::calibration::{
+    capture_label, spawn_calibration_page, update_calibration_readout, CaptureLabel,
+};
+use crate::systems::countdown::CountdownSettings;
+use crate::systems::controls::{
+    cancel_rebind, capture_rebind, spawn_controls_page, update_binding_labels, RebindTarget,
+};
+
+/// Main menu shown on startup: Play, the controller calibration and controls pages, and their camera.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Menu), spawn_main_menu)
+            .add_systems(
+                Update,
+                (
+                    handle_menu_buttons,
+                    highlight_buttons::<MenuButton>,
+                    back_or_quit,
+                    update_binding_labels,
+                    update_countdown_label,
+                )
+                    .run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(
+                Update,
+                capture_rebind
+                    .after(handle_menu_buttons)
+                    .run_if(in_state(GameState::Menu))
+                    .run_if(resource_exists::<RebindTarget>()),
+            )
+            .add_systems(OnExit(GameState::Menu), cancel_rebind)
+            .add_systems(
+                Update,
+                update_calibration_readout
+                    .run_if(in_state(GameState::Menu))
+                    .run_if(resource_exists::<AnalogInputs>()),
+            );
+    }
+}
 
 #[derive(Component)]
 pub struct MainMenuUI;
 
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum MenuButton {
+    Play,
+    Controller,
+    Controls,
+    Calibrate,
+    SaveCalibration,
+    Rebind(Action),
+    ResetBindings,
+    AssignGamepad,
+    Back,
+}
+
+/// One screen of the main menu; only one is shown at a time.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum MenuPage {
+    Main,
+    Calibration,
+    Controls,
+}
+
+/// Countdown length on the main page, set with a controller's `MenuSlider` channel.
 #[derive(Component)]
-pub struct PlayButton;
+pub struct CountdownLabel;
 
 #[derive(Component)]
 pub struct MenuCamera; // ✅ Tag for the menu camera
 
-/// Spawns the main menu UI with a Play button
-pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Spawns the main menu UI: the main page and the hidden calibration and controls pages
+pub fn spawn_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<InputBindings>,
+    countdown: Res<CountdownSettings>,
+) {
+    let text_style = TextStyle {
+        font: asset_server.load("FiraSans-Bold.ttf"),
+        font_size: 40.0,
+        color: Color::WHITE,
+    };
+    let page_style = Style {
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
     // ✅ Spawn menu camera and tag it
     commands.spawn((
         Camera2dBundle::default(),
         MenuCamera,
+        DespawnOnExit(GameState::Menu),
     ));
 
     commands.spawn((
@@ -33,67 +117,163 @@ pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         MainMenuUI,
+        DespawnOnExit(GameState::Menu),
     ))
         .with_children(|parent| {
             parent
-                .spawn((
-                    ButtonBundle {
-                        style: Style {
-                            width: Val::Px(200.0),
-                            height: Val::Px(80.0),
-                            margin: UiRect::all(Val::Px(10.0)),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        background_color: Color::GRAY.into(),
-                        ..default()
-                    },
-                    PlayButton,
-                ))
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Play",
-                        TextStyle {
-                            font: asset_server.load("FiraSans-Bold.ttf"),
-                            font_size: 40.0,
-                            color: Color::WHITE,
-                        },
+                .spawn((NodeBundle { style: page_style.clone(), ..default() }, MenuPage::Main))
+                .with_children(|page| {
+                    spawn_menu_button(page, "Play", MenuButton::Play, &text_style);
+                    spawn_menu_button(page, "Controller", MenuButton::Controller, &text_style);
+                    spawn_menu_button(page, "Controls", MenuButton::Controls, &text_style);
+                    page.spawn((
+                        TextBundle::from_section(countdown_label(&countdown), text_style.clone()),
+                        CountdownLabel,
                     ));
                 });
+
+            let hidden = Style { display: Display::None, ..page_style };
+            parent
+                .spawn((NodeBundle { style: hidden.clone(), ..default() }, MenuPage::Calibration))
+                .with_children(|page| spawn_calibration_page(page, &text_style));
+            parent
+                .spawn((NodeBundle { style: hidden, ..default() }, MenuPage::Controls))
+                .with_children(|page| {
+                    spawn_controls_page(page, &text_style, &bindings);
+                    page.spawn(NodeBundle::default()).with_children(|row| {
+                        spawn_menu_button(row, "Reset", MenuButton::ResetBindings, &text_style);
+                        spawn_menu_button(row, "Assign pad", MenuButton::AssignGamepad, &text_style);
+                        spawn_menu_button(row, "Back", MenuButton::Back, &text_style);
+                    });
+                });
         });
 }
 
-/// Handles clicking the Play button
-pub fn handle_play_button(
-    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<PlayButton>)>,
+pub fn spawn_menu_button(parent: &mut ChildBuilder, label: &str, action: MenuButton, text_style: &TextStyle) {
+    let style = Style {
+        min_width: Val::Px(200.0),
+        height: Val::Px(80.0),
+        margin: UiRect::all(Val::Px(10.0)),
+        padding: UiRect::horizontal(Val::Px(20.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let text = TextBundle::from_section(label, text_style.clone());
+    if action == MenuButton::Calibrate {
+        spawn_button(parent, style, action, (text, CaptureLabel));
+    } else {
+        spawn_button(parent, style, action, text);
+    }
+}
+
+fn show_menu_page(pages: &mut Query<(&mut Style, &MenuPage)>, shown: MenuPage) {
+    for (mut style, page) in pages.iter_mut() {
+        style.display = if *page == shown { Display::Flex } else { Display::None };
+    }
+}
+
+/// Handles the menu buttons on every page
+#[allow(clippy::too_many_arguments)]
+pub fn handle_menu_buttons(
     mut commands: Commands,
+    interaction_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
     mut next_state: ResMut<NextState<GameState>>,
-    menu_camera_query: Query<Entity, With<MenuCamera>>, // ✅ To remove menu camera
-    ui_query: Query<Entity, With<MainMenuUI>>,           // ✅ Optional: to remove the menu UI
+    mut pages: Query<(&mut Style, &MenuPage)>,
+    mut labels: Query<&mut Text, With<CaptureLabel>>,
+    mut analog: Option<ResMut<AnalogInputs>>,
+    mut analog_settings: Option<ResMut<AnalogSettings>>,
+    mut bindings: ResMut<InputBindings>,
+    mut player_gamepad: ResMut<PlayerGamepad>,
 ) {
-    for (interaction, mut color) in interaction_query.iter_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                // ✅ Despawn menu camera
-                for cam_entity in menu_camera_query.iter() {
-                    commands.entity(cam_entity).despawn();
-                }
-
-                // ✅ Despawn the entire menu UI
-                for ui_entity in ui_query.iter() {
-                    commands.entity(ui_entity).despawn_recursive();
-                }
-
-                next_state.set(GameState::Running);
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Play => {
+                // Menu camera and UI are despawned on leaving the menu
+                next_state.set(GameState::Countdown);
                 println!("▶️ Play button clicked!");
             }
-            Interaction::Hovered => {
-                *color = Color::DARK_GRAY.into();
+            MenuButton::Controller => show_menu_page(&mut pages, MenuPage::Calibration),
+            MenuButton::Controls => show_menu_page(&mut pages, MenuPage::Controls),
+            MenuButton::Back => {
+                commands.remove_resource::<RebindTarget>();
+                show_menu_page(&mut pages, MenuPage::Main);
+            }
+            MenuButton::Rebind(action) => commands.insert_resource(RebindTarget::new(*action)),
+            MenuButton::AssignGamepad => {
+                // The next pad to press a button claims the player
+                player_gamepad.0 = None;
+                println!("🎮 Press a button on the gamepad to use");
+            }
+            MenuButton::ResetBindings => {
+                commands.remove_resource::<RebindTarget>();
+                *bindings = InputBindings::default();
+                match bindings.save(std::path::Path::new(BINDINGS_PATH)) {
+                    Ok(()) => println!("🎮 Bindings reset and saved to {BINDINGS_PATH}"),
+                    Err(err) => eprintln!("⚠️ {err}"),
+                }
+            }
+            MenuButton::Calibrate => {
+                let (Some(analog), Some(settings)) = (analog.as_mut(), analog_settings.as_mut()) else {
+                    continue;
+                };
+                if analog.capture.is_some() {
+                    analog.finish_capture(settings);
+                } else {
+                    analog.start_capture();
+                }
+                for mut text in labels.iter_mut() {
+                    text.sections[0].value = capture_label(analog).to_string();
+                }
             }
-            Interaction::None => {
-                *color = Color::GRAY.into();
+            MenuButton::SaveCalibration => {
+                let Some(settings) = analog_settings.as_ref() else { continue };
+                match settings.save(std::path::Path::new(ANALOG_CONFIG_PATH)) {
+                    Ok(()) => println!("🎚️ Calibration saved to {ANALOG_CONFIG_PATH}"),
+                    Err(err) => eprintln!("⚠️ {err}"),
+                }
             }
         }
     }
 }
+
+/// The Back action returns to the main page, and quits the game from there. Neither
+/// happens while a key is being bound, since that key may well be Back's.
+pub fn back_or_quit(
+    actions: Res<ActionState>,
+    rebinding: Option<Res<RebindTarget>>,
+    mut pages: Query<(&mut Style, &MenuPage)>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !actions.just_pressed(Action::Back) || rebinding.is_some() {
+        return;
+    }
+
+    let on_main_page = pages
+        .iter()
+        .any(|(style, page)| *page == MenuPage::Main && style.display != Display::None);
+    if on_main_page {
+        exit.send(AppExit);
+    } else {
+        show_menu_page(&mut pages, MenuPage::Main);
+    }
+}
+
+fn countdown_label(countdown: &CountdownSettings) -> String {
+    format!("Countdown: {:.0} s", countdown.seconds)
+}
+
+pub fn update_countdown_label(
+    countdown: Res<CountdownSettings>,
+    mut labels: Query<&mut Text, With<CountdownLabel>>,
+) {
+    if !countdown.is_changed() {
+        return;
+    }
+    for mut text in labels.iter_mut() {
+        text.sections[0].value = countdown_label(&countdown);
+    }
+}
@@ -0,0 +1,18 @@
+pub mod setup;
+pub mod button;
+pub mod obstacles;
+pub mod collision;
+pub mod game_over;
+pub mod score;
+pub mod restart;
+pub mod coin;
+pub mod shop;
+pub mod menu;
+pub mod movement;
+pub mod interpolation;
+pub mod cleanup;
+pub mod pause;
+pub mod countdown;
+pub mod navigation;
+pub mod calibration;
+pub mod controls;
@@ -1,45 +1,88 @@
 use bevy::prelude::*;
 use crate::components::Player;
-use crate::constants::{GRAVITY, JUMP_VELOCITY, GROUND_Y, CEILING_Y};
-use crate::resources::Score;
+use crate::config::GameConfig;
+use crate::resources::{InputScale, JumpRelease, JumpRequest, Score};
+use crate::events::GameEvent;
+use crate::input::{Action, ActionState};
 
+pub fn read_jump_input(
+    actions: Res<ActionState>,
+    mut jump: ResMut<JumpRequest>,
+    mut release: ResMut<JumpRelease>,
+) {
+    if actions.just_pressed(Action::Jump) || actions.just_pressed(Action::Flip) {
+        jump.0 = true;
+    }
+    if actions.just_released(Action::Jump) || actions.just_released(Action::Flip) {
+        release.0 = true;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn player_movement(
-    keyboard_input: Res<Input<KeyCode>>,
+    mut jump: ResMut<JumpRequest>,
+    mut release: ResMut<JumpRelease>,
     time: Res<Time>,
     mut query: Query<(&mut Transform, &mut Player)>,
     score: Res<Score>,
+    config: Res<GameConfig>,
+    scale: Res<InputScale>,
+    mut events: EventWriter<GameEvent>,
 ) {
     if let Ok((mut transform, mut player)) = query.get_single_mut() {
         let delta_time = time.delta_seconds();
 
-        let should_jump = keyboard_input.just_pressed(KeyCode::Space);
+        let pressed = std::mem::take(&mut jump.0);
+        if pressed {
+            player.jump_held = true;
+        }
+        if std::mem::take(&mut release.0) {
+            player.jump_held = false;
+        }
+        // A press that can't be used right away waits in the buffer for a while
+        let wants_jump = pressed || player.jump_buffer > 0.0;
+        let mut used = false;
 
-        if score.0 < 100.0 {
+        if score.0 < config.flip_score_threshold {
             // Standard gravity jump
-            if should_jump && player.on_ground {
-                player.velocity = JUMP_VELOCITY;
+            if player.on_ground {
+                player.coyote_time = config.coyote_time;
+            }
+            if wants_jump && (player.on_ground || player.coyote_time > 0.0) {
+                player.velocity = config.jump_velocity * scale.jump;
                 player.on_ground = false;
+                player.coyote_time = 0.0;
+                used = true;
             }
-            player.velocity += GRAVITY * delta_time;
+            // Letting go early turns a full jump into a short hop
+            let cut_velocity = config.jump_velocity * scale.jump * config.jump_release_cut;
+            if !player.jump_held && player.velocity > cut_velocity {
+                player.velocity = cut_velocity;
+            }
+            player.velocity += config.gravity * delta_time;
             transform.translation.y += player.velocity * delta_time;
 
-            if transform.translation.y <= GROUND_Y {
-                transform.translation.y = GROUND_Y;
+            if transform.translation.y <= config.ground_y {
+                transform.translation.y = config.ground_y;
                 player.velocity = 0.0;
                 player.on_ground = true;
             }
         } else {
-            // Ceiling flipping jump
+            // Ceiling flipping jump; a flip starts once the previous one has landed
             player.velocity = 0.0;
-            if should_jump {
+            if wants_jump && player.on_ground {
                 player.flipped = !player.flipped;
+                player.on_ground = false;
+                used = true;
+                events.send(GameEvent::Flipped { to_ceiling: player.flipped });
             }
 
-            let target_y = if player.flipped { CEILING_Y } else { GROUND_Y };
-            let move_speed = 500.0;
+            let target_y = if player.flipped { config.ceiling_y } else { config.ground_y };
+            let move_speed = config.flip_speed * scale.flip_speed;
 
             if (transform.translation.y - target_y).abs() < move_speed * delta_time {
                 transform.translation.y = target_y;
+                player.on_ground = true;
             } else {
                 let direction = if transform.translation.y < target_y { 1.0 } else { -1.0 };
                 transform.translation.y += move_speed * delta_time * direction;
@@ -51,5 +94,20 @@ pub fn player_movement(
                 Quat::IDENTITY
             };
         }
+
+        let buffer_time = if score.0 < config.flip_score_threshold {
+            config.jump_buffer_time
+        } else {
+            config.flip_buffer_time
+        };
+        player.jump_buffer = if used {
+            0.0
+        } else if pressed {
+            buffer_time - delta_time
+        } else {
+            player.jump_buffer - delta_time
+        }
+        .max(0.0);
+        player.coyote_time = (player.coyote_time - delta_time).max(0.0);
     }
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+use crate::input::{Action, ActionSet, ActionState};
+
+/// Moves a focus ring between on-screen buttons and presses the focused one,
+/// so menus and the shop work without a mouse.
+pub struct MenuNavigationPlugin;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuInput>()
+            .init_resource::<MenuFocus>()
+            .add_systems(PreUpdate, send_menu_input.after(ActionSet::Extend))
+            // After the UI's own focus pass, so a navigated press isn't overwritten by the cursor
+            .add_systems(
+                PreUpdate,
+                navigate_menus.after(UiSystem::Focus).after(send_menu_input),
+            );
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Select,
+}
+
+#[derive(Resource, Default)]
+pub struct MenuFocus {
+    pub focused: Option<Entity>,
+    /// Button pressed through navigation last frame, released again this frame.
+    pressed: Option<Entity>,
+}
+
+const FOCUS_OUTLINE: Outline = Outline {
+    width: Val::Px(3.0),
+    offset: Val::Px(2.0),
+    color: Color::YELLOW,
+};
+
+pub fn send_menu_input(actions: Res<ActionState>, mut inputs: EventWriter<MenuInput>) {
+    for (action, input) in [
+        (Action::NavigateUp, MenuInput::Up),
+        (Action::NavigateDown, MenuInput::Down),
+        (Action::Confirm, MenuInput::Select),
+    ] {
+        if actions.just_pressed(action) {
+            inputs.send(input);
+        }
+    }
+}
+
+pub fn navigate_menus(
+    mut commands: Commands,
+    mut inputs: EventReader<MenuInput>,
+    mut focus: ResMut<MenuFocus>,
+    mut buttons: Query<(Entity, &Node, &GlobalTransform, &mut Interaction), With<Button>>,
+) {
+    if let Some(entity) = focus.pressed.take() {
+        if let Ok((_, _, _, mut interaction)) = buttons.get_mut(entity) {
+            interaction.set_if_neq(Interaction::None);
+        }
+    }
+
+    // Visible buttons in reading order: top to bottom, then left to right
+    let mut order: Vec<(Entity, Vec3)> = buttons
+        .iter()
+        .filter(|(_, node, _, _)| node.size() != Vec2::ZERO)
+        .map(|(entity, _, transform, _)| (entity, transform.translation()))
+        .collect();
+    order.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    let current = focus
+        .focused
+        .and_then(|focused| order.iter().position(|(entity, _)| *entity == focused));
+
+    let mut next = current;
+    for input in inputs.read() {
+        match input {
+            MenuInput::Up | MenuInput::Down if order.is_empty() => {}
+            MenuInput::Up => {
+                next = Some(next.map_or(order.len() - 1, |index| {
+                    (index + order.len() - 1) % order.len()
+                }));
+            }
+            MenuInput::Down => {
+                next = Some(next.map_or(0, |index| (index + 1) % order.len()));
+            }
+            MenuInput::Select => {
+                let Some(index) = next else { continue };
+                let entity = order[index].0;
+                if let Ok((_, _, _, mut interaction)) = buttons.get_mut(entity) {
+                    *interaction = Interaction::Pressed;
+                    focus.pressed = Some(entity);
+                }
+            }
+        }
+    }
+
+    let next_entity = next.map(|index| order[index].0);
+    if next_entity != focus.focused || current.is_none() {
+        if let Some(old) = focus.focused {
+            if let Some(mut entity) = commands.get_entity(old) {
+                entity.remove::<Outline>();
+            }
+        }
+        if let Some(new) = next_entity {
+            commands.entity(new).insert(FOCUS_OUTLINE);
+        }
+        focus.focused = next_entity;
+    }
+}
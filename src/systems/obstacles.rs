@@ -1,61 +1,77 @@
 use bevy::prelude::*;
-use rand::{rng, Rng};
+use rand::Rng;
 use std::time::Duration;
 
-use crate::components::Obstacle;
-use crate::resources::{SpawnTimer, Score};
-use crate::constants::{
-    MIN_SPAWN_TIME, MAX_SPAWN_TIME, OBSTACLE_SIZE, GROUND_Y, CEILING_Y, OBSTACLE_SPEED,
+use crate::components::{Hazard, Obstacle, RunScoped};
+use crate::systems::interpolation::Interpolated;
+use crate::resources::{SpawnTimer, Score, GameAssets, GameRng};
+use crate::config::GameConfig;
+use crate::constants::OBSTACLE_SIZE;
+use crate::hitbox::Hitbox;
+
+/// How an obstacle is drawn and the outline it collides with, standing upright.
+pub struct ObstacleDef {
+    pub size: Vec2,
+    pub outline: &'static [Vec2],
+}
+
+/// A spike from `spike.png`: a triangle on its base, tip at the top center. Ceiling
+/// spikes are the same spike drawn with `flip_y`, which flips the outline too.
+pub const SPIKE: ObstacleDef = ObstacleDef {
+    size: OBSTACLE_SIZE,
+    outline: &[
+        Vec2::new(-OBSTACLE_SIZE.x / 2.0, -OBSTACLE_SIZE.y / 2.0),
+        Vec2::new(OBSTACLE_SIZE.x / 2.0, -OBSTACLE_SIZE.y / 2.0),
+        Vec2::new(0.0, OBSTACLE_SIZE.y / 2.0),
+    ],
 };
 
+fn obstacle_bundle(def: &ObstacleDef, texture: Handle<Image>, translation: Vec3, flip_y: bool) -> impl Bundle {
+    (
+        SpriteBundle {
+            texture,
+            sprite: Sprite {
+                custom_size: Some(def.size),
+                flip_y,
+                ..default()
+            },
+            transform: Transform::from_translation(translation),
+            ..default()
+        },
+        Obstacle,
+        Hazard,
+        Hitbox::polygon(def.outline),
+        Interpolated::at(translation),
+        RunScoped,
+    )
+}
+
 pub fn spawn_obstacles(
     mut commands: Commands,
     time: Res<Time>,
     mut timer: ResMut<SpawnTimer>,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     score: Res<Score>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
 ) {
     timer.0.tick(time.delta());
 
     if timer.0.finished() {
-        let mut rng = rng();
-        let base_spawn_time = rng.gen_range(MIN_SPAWN_TIME..=MAX_SPAWN_TIME);
+        let base_spawn_time = rng.random_range(config.min_spawn_time..=config.max_spawn_time);
         let adjusted_spawn_time = (base_spawn_time / score.1).max(0.5);
         timer.0.set_duration(Duration::from_secs_f32(adjusted_spawn_time));
 
-        let obstacle_texture = asset_server.load("spike.png");
-        let floor_spike_x = rng.gen_range(350.0..450.0);
-        let ceiling_spike_x = rng.gen_range(350.0..450.0);
+        let obstacle_texture = assets.spike.clone();
+        let floor_spike = Vec3::new(rng.random_range(350.0..450.0), config.ground_y, 0.0);
+        let ceiling_spike = Vec3::new(rng.random_range(350.0..450.0), config.ceiling_y, 0.0);
 
         // Spawn floor spike
-        commands.spawn((
-            SpriteBundle {
-                texture: obstacle_texture.clone(),
-                sprite: Sprite {
-                    custom_size: Some(OBSTACLE_SIZE),
-                    ..default()
-                },
-                transform: Transform::from_xyz(floor_spike_x, GROUND_Y, 0.0),
-                ..default()
-            },
-            Obstacle,
-        ));
+        commands.spawn(obstacle_bundle(&SPIKE, obstacle_texture.clone(), floor_spike, false));
 
         // Spawn ceiling spike if score high enough
-        if score.0 >= 100.0 {
-            commands.spawn((
-                SpriteBundle {
-                    texture: obstacle_texture,
-                    sprite: Sprite {
-                        custom_size: Some(OBSTACLE_SIZE),
-                        flip_y: true,
-                        ..default()
-                    },
-                    transform: Transform::from_xyz(ceiling_spike_x, CEILING_Y, 0.0),
-                    ..default()
-                },
-                Obstacle,
-            ));
+        if score.0 >= config.flip_score_threshold {
+            commands.spawn(obstacle_bundle(&SPIKE, obstacle_texture, ceiling_spike, true));
         }
     }
 }
@@ -63,14 +79,15 @@ pub fn spawn_obstacles(
 pub fn move_obstacles(
     mut commands: Commands,
     time: Res<Time>,
+    config: Res<GameConfig>,
     mut query: Query<(Entity, &mut Transform), With<Obstacle>>,
 ) {
     let delta_time = time.delta_seconds();
 
     for (entity, mut transform) in query.iter_mut() {
-        transform.translation.x += OBSTACLE_SPEED * delta_time;
+        transform.translation.x += config.obstacle_speed * delta_time;
 
-        if transform.translation.x < -400.0 {
+        if transform.translation.x < config.despawn_x {
             commands.entity(entity).despawn();
         }
     }
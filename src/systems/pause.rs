@@ -0,0 +1,274 @@
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+use crate::components::DespawnOnExit;
+use crate::input::{Action, ActionState};
+use crate::resources::{ActiveRun, GameAssets, GameState};
+use crate::systems::button::{highlight_buttons, spawn_button};
+
+/// Pause overlay: the Pause action or losing window focus pauses a run and freezes the clock.
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PauseSettings>()
+            // Normally registered by WindowPlugin; headless apps have none
+            .add_event::<WindowFocused>()
+            .add_systems(
+                Update,
+                toggle_pause
+                    // Sees the settings page before Back hides it
+                    .before(back_to_pause_page)
+                    .run_if(in_state(GameState::Running).or_else(in_state(GameState::Paused))),
+            )
+            // Also during the countdown, so a run doesn't start with nobody at the window
+            .add_systems(
+                Update,
+                pause_on_focus_loss
+                    .run_if(in_state(GameState::Running).or_else(in_state(GameState::Countdown))),
+            )
+            .add_systems(OnEnter(GameState::Running), pause_if_unfocused)
+            .add_systems(OnEnter(GameState::Paused), (freeze_time, spawn_pause_menu))
+            .add_systems(OnExit(GameState::Paused), unfreeze_time)
+            .add_systems(
+                Update,
+                (handle_pause_buttons, highlight_buttons::<PauseButton>, back_to_pause_page)
+                    .run_if(in_state(GameState::Paused)),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct PauseSettings {
+    pub pause_on_focus_loss: bool,
+}
+
+impl Default for PauseSettings {
+    fn default() -> Self {
+        Self { pause_on_focus_loss: true }
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum PauseButton {
+    Resume,
+    Restart,
+    Settings,
+    QuitToMenu,
+    ToggleAutoPause,
+    Back,
+}
+
+#[derive(Component)]
+pub struct PauseMainPage;
+
+#[derive(Component)]
+pub struct PauseSettingsPage;
+
+/// Both pages of the pause overlay, telling the settings page apart.
+type PauseSubPages<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Style, Has<PauseSettingsPage>),
+    Or<(With<PauseMainPage>, With<PauseSettingsPage>)>,
+>;
+
+#[derive(Component)]
+pub struct AutoPauseLabel;
+
+/// Pause pauses and resumes, except on the settings page: Esc is bound to both Pause
+/// and Back by default, and there it should only go back a page.
+pub fn toggle_pause(
+    actions: Res<ActionState>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    settings_pages: Query<&Style, With<PauseSettingsPage>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+    if settings_pages.iter().any(|style| style.display != Display::None) {
+        return;
+    }
+
+    match state.get() {
+        GameState::Running => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Countdown),
+        _ => {}
+    }
+}
+
+pub fn pause_on_focus_loss(
+    mut focus_events: EventReader<WindowFocused>,
+    settings: Res<PauseSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let lost_focus = focus_events.read().any(|event| !event.focused);
+    if lost_focus && settings.pause_on_focus_loss {
+        next_state.set(GameState::Paused);
+    }
+}
+
+/// Catches focus lost before the run got going, e.g. a run started from the controller.
+pub fn pause_if_unfocused(
+    windows: Query<&Window>,
+    settings: Res<PauseSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings.pause_on_focus_loss && windows.iter().any(|window| !window.focused) {
+        next_state.set(GameState::Paused);
+    }
+}
+
+/// Stops virtual time, so fixed ticks, spawn timers and score all hold still
+pub fn freeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn unfreeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn auto_pause_label(settings: &PauseSettings) -> String {
+    let value = if settings.pause_on_focus_loss { "On" } else { "Off" };
+    format!("Pause on focus loss: {value}")
+}
+
+pub fn spawn_pause_menu(mut commands: Commands, assets: Res<GameAssets>, settings: Res<PauseSettings>) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 32.0,
+        color: Color::WHITE,
+    };
+    let page_style = Style {
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        DespawnOnExit(GameState::Paused),
+    ))
+        .with_children(|parent| {
+            parent
+                .spawn((NodeBundle { style: page_style.clone(), ..default() }, PauseMainPage))
+                .with_children(|page| {
+                    page.spawn(TextBundle::from_section(
+                        "Paused",
+                        TextStyle { font_size: 50.0, ..text_style.clone() },
+                    ));
+                    spawn_pause_button(page, "Resume", PauseButton::Resume, &text_style);
+                    spawn_pause_button(page, "Restart", PauseButton::Restart, &text_style);
+                    spawn_pause_button(page, "Settings", PauseButton::Settings, &text_style);
+                    spawn_pause_button(page, "Quit to Menu", PauseButton::QuitToMenu, &text_style);
+                });
+
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style { display: Display::None, ..page_style },
+                        ..default()
+                    },
+                    PauseSettingsPage,
+                ))
+                .with_children(|page| {
+                    page.spawn(TextBundle::from_section(
+                        "Settings",
+                        TextStyle { font_size: 50.0, ..text_style.clone() },
+                    ));
+                    spawn_pause_button(
+                        page,
+                        &auto_pause_label(&settings),
+                        PauseButton::ToggleAutoPause,
+                        &text_style,
+                    );
+                    spawn_pause_button(page, "Back", PauseButton::Back, &text_style);
+                });
+        });
+}
+
+fn spawn_pause_button(parent: &mut ChildBuilder, label: &str, action: PauseButton, text_style: &TextStyle) {
+    let style = Style {
+        width: Val::Px(360.0),
+        height: Val::Px(60.0),
+        margin: UiRect::all(Val::Px(8.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let text = TextBundle::from_section(label, text_style.clone());
+    if action == PauseButton::ToggleAutoPause {
+        spawn_button(parent, style, action, (text, AutoPauseLabel));
+    } else {
+        spawn_button(parent, style, action, text);
+    }
+}
+
+pub fn handle_pause_buttons(
+    interaction_query: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut settings: ResMut<PauseSettings>,
+    mut pages: PauseSubPages,
+    mut labels: Query<&mut Text, With<AutoPauseLabel>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseButton::Resume => next_state.set(GameState::Countdown),
+            PauseButton::Restart => {
+                // Dropping the active run makes the countdown set up a fresh one
+                commands.remove_resource::<ActiveRun>();
+                next_state.set(GameState::Countdown);
+            }
+            PauseButton::QuitToMenu => next_state.set(GameState::Menu),
+            PauseButton::Settings | PauseButton::Back => {
+                show_pause_page(&mut pages, *button == PauseButton::Settings);
+            }
+            PauseButton::ToggleAutoPause => {
+                settings.pause_on_focus_loss = !settings.pause_on_focus_loss;
+                for mut text in labels.iter_mut() {
+                    text.sections[0].value = auto_pause_label(&settings);
+                }
+            }
+        }
+    }
+}
+
+fn show_pause_page(
+    pages: &mut PauseSubPages<'_, '_>,
+    show_settings: bool,
+) {
+    for (mut style, is_settings_page) in pages.iter_mut() {
+        style.display = if is_settings_page == show_settings {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// The Back action leaves the settings page; on the main page, Pause resumes instead.
+pub fn back_to_pause_page(
+    actions: Res<ActionState>,
+    mut pages: PauseSubPages,
+) {
+    if actions.just_pressed(Action::Back) {
+        show_pause_page(&mut pages, false);
+    }
+}
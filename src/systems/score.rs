@@ -1,20 +1,28 @@
 use bevy::prelude::*;
 use bevy::ecs::schedule::State;
 
-use crate::resources::{Score, GameState};
+use crate::components::ScoreText;
+use crate::resources::{Score, GameState, RunTick};
+use crate::events::GameEvent;
 
 pub fn update_score(
     time: Res<Time>,
     mut score: ResMut<Score>,
-    mut text_query: Query<&mut Text>,
+    mut text_query: Query<&mut Text, With<ScoreText>>,
     game_state: Res<State<GameState>>,
+    mut events: EventWriter<GameEvent>,
 ) {
     if game_state.get() == &GameState::Running {
         score.0 += time.delta_seconds() * 10.0;
         score.1 = 1.0 + (score.0 / 500.0);
+        events.send(GameEvent::ScoreChanged(score.0 as u32));
 
         if let Ok(mut text) = text_query.get_single_mut() {
             text.sections[0].value = format!("Score: {:.0}", score.0);
         }
     }
 }
+
+pub fn advance_run_tick(mut tick: ResMut<RunTick>) {
+    tick.0 += 1;
+}
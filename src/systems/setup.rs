@@ -1,66 +1,108 @@
 use bevy::prelude::*;
-use crate::constants::GROUND_Y;
-use crate::components::{Player, MainCamera};
-use crate::resources::CurrentSkin;
+use crate::config::GameConfig;
+use crate::constants::{LOGICAL_SCREEN_SIZE, PLAYER_SIZE};
+use crate::hitbox::Hitbox;
+use crate::components::{Player, MainCamera, RunScoped, ScoreText};
+use crate::resources::{
+    ActiveRun, CurrentSkin, GameAssets, GameRng, JumpRelease, JumpRequest, RngSeed, RunTick, Score, SpawnTimer,
+};
+use crate::systems::coin::CoinSpawnTimer;
+use crate::systems::interpolation::Interpolated;
+
+/// Systems that build a new run. They are skipped when the countdown is
+/// entered to resume a run that is already in progress.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunSetup;
+
+/// Reseeds the RNG and rewinds run timers so a run depends only on its seed and inputs.
+/// A jump still pending from the last run, e.g. pressed together with pause, is dropped.
+#[allow(clippy::too_many_arguments)]
+pub fn reset_run_state(
+    mut commands: Commands,
+    seed: Res<RngSeed>,
+    mut rng: ResMut<GameRng>,
+    mut score: ResMut<Score>,
+    mut tick: ResMut<RunTick>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+    mut coin_timer: ResMut<CoinSpawnTimer>,
+    mut jump: ResMut<JumpRequest>,
+    mut release: ResMut<JumpRelease>,
+) {
+    *rng = GameRng::from_seed(seed.0.unwrap_or_else(rand::random));
+    *score = Score::default();
+    tick.0 = 0;
+    *spawn_timer = SpawnTimer::default();
+    coin_timer.0.reset();
+    jump.0 = false;
+    release.0 = false;
+    commands.insert_resource(ActiveRun);
+    println!("🎲 Run seed: {}", rng.seed());
+}
 
 pub fn setup(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     windows: Query<&Window>,
     skin: Res<CurrentSkin>,
-    camera_query: Query<Entity, With<Camera>>,
+    config: Res<GameConfig>,
 ) {
-    // ✅ Despawn any existing cameras before spawning a new one
-    for cam_entity in camera_query.iter() {
-        commands.entity(cam_entity).despawn();
-    }
-
-    // ✅ Spawn the camera and tag it
     commands.spawn((
         Camera2dBundle::default(),
         MainCamera,
+        RunScoped,
     ));
 
-    let window = windows.single();
-    let window_width = window.width();
-    let window_height = window.height();
+    // Headless runs have no window; fall back to the fixed logical play area
+    let screen_size = windows
+        .get_single()
+        .map(|window| Vec2::new(window.width(), window.height()))
+        .unwrap_or(LOGICAL_SCREEN_SIZE);
 
-    let background_texture = asset_server.load("background.png");
-    commands.spawn(SpriteBundle {
-        texture: background_texture,
-        transform: Transform::from_xyz(0.0, 0.0, -1.0),
-        sprite: Sprite {
-            custom_size: Some(Vec2::new(window_width, window_height)),
+    commands.spawn((
+        SpriteBundle {
+            texture: assets.background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, -1.0),
+            sprite: Sprite {
+                custom_size: Some(screen_size),
+                ..default()
+            },
             ..default()
         },
-        ..default()
-    });
+        RunScoped,
+    ));
 
-    let player_texture = asset_server.load("player.png");
     let skin_color = skin.color;
+    let player_translation = Vec3::new(-200.0, config.ground_y, 0.0);
 
     commands.spawn((
         SpriteBundle {
-            texture: player_texture,
+            texture: assets.player.clone(),
             sprite: Sprite {
                 color: skin_color,
+                custom_size: Some(PLAYER_SIZE),
                 ..default()
             },
-            transform: Transform::from_xyz(-200.0, GROUND_Y, 0.0),
+            transform: Transform::from_translation(player_translation),
             ..default()
         },
         Player {
             velocity: 0.0,
             on_ground: true,
             flipped: false,
+            jump_buffer: 0.0,
+            coyote_time: 0.0,
+            jump_held: false,
         },
+        Hitbox::new(PLAYER_SIZE),
+        Interpolated::at(player_translation),
+        RunScoped,
     ));
 
     commands.spawn((
         TextBundle::from_section(
             "Score: 0",
             TextStyle {
-                font: asset_server.load("FiraSans-Bold.ttf"),
+                font: assets.font.clone(),
                 font_size: 40.0,
                 color: Color::WHITE,
             },
@@ -71,5 +113,7 @@ pub fn setup(
                 left: Val::Percent(50.0),
                 ..default()
             }),
+        ScoreText,
+        RunScoped,
     ));
 }
\ No newline at end of file
@@ -1,5 +1,18 @@
 use bevy::prelude::*;
-use crate::resources::{CoinWallet, CurrentSkin};
+use crate::components::DespawnOnExit;
+use crate::resources::{CoinWallet, CurrentSkin, GameState};
+
+/// Skin shop shown on the game-over screen.
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoinWallet>()
+            .init_resource::<CurrentSkin>()
+            .add_systems(OnEnter(GameState::GameOver), shop_ui) // ✅ Show shop on death
+            .add_systems(Update, handle_buy_button.run_if(in_state(GameState::GameOver))); // ✅ Allow clicking while dead
+    }
+}
 
 #[derive(Component)]
 pub struct ShopUI;
@@ -34,6 +47,7 @@ pub fn shop_ui(
             ..default()
         },
         ShopUI,
+        DespawnOnExit(GameState::GameOver),
     )).with_children(|parent| {
         parent.spawn(TextBundle::from_section(
             format!("Coins: {}", wallet.coins),
@@ -70,11 +84,10 @@ pub fn shop_ui(
     });
 }
 
+type BuyButtonChanged = (Changed<Interaction>, With<BuyButton>);
+
 pub fn handle_buy_button(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<BuyButton>),
-    >,
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), BuyButtonChanged>,
     mut skin: ResMut<CurrentSkin>,
     mut wallet: ResMut<CoinWallet>,
 ) {
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rust_spil::components::{Coin, Hazard, Obstacle, Pickup, Player, RunScoped};
+use rust_spil::headless_app;
+use rust_spil::hitbox::Hitbox;
+use rust_spil::resources::{CoinWallet, GameState, RngSeed};
+use rust_spil::systems::interpolation::Interpolated;
+use rust_spil::systems::obstacles::SPIKE;
+
+/// One gameplay tick per second: everything moves several hitbox widths per tick.
+fn slow_app() -> App {
+    let tick = Duration::from_secs(1);
+    let mut app = headless_app(tick);
+    app.insert_resource(RngSeed(Some(7)));
+    // Otherwise each frame's delta is capped well below the tick
+    app.world.resource_mut::<Time<Virtual>>().set_max_delta(tick * 10);
+    app
+}
+
+fn state(app: &App) -> GameState {
+    *app.world.resource::<State<GameState>>().get()
+}
+
+/// Runs until the player is in play and returns where it is. Anything spawned 300
+/// ahead of it passes the player between two ticks without ever overlapping it.
+fn start_run(app: &mut App) -> Vec3 {
+    while state(app) != GameState::Running {
+        app.update();
+    }
+    app.world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(&app.world)
+        .translation
+}
+
+#[test]
+fn large_tick_still_hits_spikes() {
+    let mut app = slow_app();
+    let translation = start_run(&mut app) + Vec3::new(300.0, 0.0, 0.0);
+    app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(translation)),
+        Obstacle,
+        Hazard,
+        Hitbox::polygon(SPIKE.outline),
+        Interpolated::at(translation),
+        RunScoped,
+    ));
+
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(state(&app), GameState::GameOver);
+}
+
+#[test]
+fn large_tick_still_picks_up_coins() {
+    let mut app = slow_app();
+    let translation = start_run(&mut app) + Vec3::new(300.0, 0.0, 0.0);
+    app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(translation)),
+        Coin,
+        Pickup,
+        Hitbox::new(Vec2::splat(30.0)),
+        Interpolated::at(translation),
+        RunScoped,
+    ));
+
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(app.world.resource::<CoinWallet>().coins, 1);
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rust_spil::headless_app;
+use rust_spil::replay::{PlaybackPlugin, PlaybackResult, RecordPlugin, Recording};
+use rust_spil::resources::{GameState, JumpRequest, RngSeed};
+
+fn run_until_game_over(app: &mut App, mut before_update: impl FnMut(&mut App, u32)) {
+    for frame in 0..10_000 {
+        before_update(app, frame);
+        app.update();
+        if *app.world.resource::<State<GameState>>().get() == GameState::GameOver {
+            return;
+        }
+    }
+    panic!("run never ended");
+}
+
+#[test]
+fn recorded_run_replays_to_the_same_result() {
+    let path = std::env::temp_dir().join(format!("rust_spil_replay_{}.ron", std::process::id()));
+
+    let mut app = headless_app(Duration::from_secs_f64(1.0 / 60.0));
+    app.insert_resource(RngSeed(Some(5)))
+        .add_plugins(RecordPlugin { path: path.clone() });
+    // Jumping regularly outlives an idle run, so the replay only matches if the jumps do
+    run_until_game_over(&mut app, |app, frame| {
+        if frame % 40 == 20 {
+            app.world.resource_mut::<JumpRequest>().0 = true;
+        }
+    });
+
+    let recording = Recording::load(&path).expect("run was recorded");
+    std::fs::remove_file(&path).ok();
+    assert!(!recording.jumps.is_empty());
+
+    let mut app = headless_app(recording.timestep);
+    app.add_plugins(PlaybackPlugin { recording: recording.clone() });
+    run_until_game_over(&mut app, |_, _| {});
+
+    let result = app.world.resource::<PlaybackResult>();
+    assert!(result.matches, "{result:?} vs recorded {:?} / {:?}", recording.end_tick, recording.final_score);
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rust_spil::headless_app;
+use rust_spil::resources::{GameState, RngSeed};
+
+fn run_until_game_over(app: &mut App) {
+    for _ in 0..10_000 {
+        app.update();
+        if *app.world.resource::<State<GameState>>().get() == GameState::GameOver {
+            return;
+        }
+    }
+    panic!("run never ended");
+}
+
+#[test]
+fn restarting_does_not_leak_entities() {
+    let mut app = headless_app(Duration::from_secs_f64(1.0 / 60.0));
+    app.insert_resource(RngSeed(Some(7)));
+
+    run_until_game_over(&mut app);
+    let after_first_run = app.world.entities().len();
+
+    for _ in 0..6 {
+        app.world.resource_mut::<NextState<GameState>>().set(GameState::Countdown);
+        run_until_game_over(&mut app);
+        assert_eq!(app.world.entities().len(), after_first_run);
+    }
+}
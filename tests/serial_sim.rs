@@ -0,0 +1,72 @@
+#![cfg(unix)]
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use rust_spil::headless_app;
+use rust_spil::resources::{GameState, RngSeed};
+use rust_spil::serial::analog::{AnalogMapping, AnalogSettings, ChannelCalibration};
+use rust_spil::serial::sim::VirtualController;
+use rust_spil::serial::{ControllerStatus, InputLatency, SharedSerialStats};
+use rust_spil::systems::countdown::CountdownSettings;
+use rust_spil::SerialPlugin;
+
+const WAIT: Duration = Duration::from_secs(3);
+
+/// Keeps the game ticking while the listener thread catches up, until `done` holds.
+fn update_until(app: &mut App, mut done: impl FnMut(&mut App) -> bool) -> bool {
+    let deadline = Instant::now() + WAIT;
+    while Instant::now() < deadline {
+        app.update();
+        if done(app) {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    false
+}
+
+#[test]
+fn game_talks_to_a_virtual_controller() {
+    let controller = VirtualController::new().expect("pseudo-terminal");
+    let mut app = headless_app(Duration::from_secs_f64(1.0 / 60.0));
+    app.insert_resource(RngSeed(Some(7)))
+        .insert_resource(controller.serial_settings())
+        .add_plugins(SerialPlugin);
+
+    assert!(update_until(&mut app, |app| {
+        *app.world.resource::<State<GameState>>().get() == GameState::Running
+            && matches!(app.world.resource::<ControllerStatus>(), ControllerStatus::Connected { .. })
+    }));
+    assert!(controller.wait_for_line("STATE RUNNING", WAIT));
+
+    controller.hello("sim-pad").unwrap();
+    assert!(controller.wait_for_line("HELLO 1 rust_spil", WAIT));
+
+    controller.send("JUMP").unwrap();
+    assert!(update_until(&mut app, |app| app.world.resource::<InputLatency>().last.is_some()));
+
+    controller.send("WIGGLE").unwrap();
+    assert!(update_until(&mut app, |app| {
+        app.world.resource::<SharedSerialStats>().0.lock().unwrap().malformed == 1
+    }));
+}
+
+#[test]
+fn menu_slider_sets_countdown_length() {
+    let controller = VirtualController::new().expect("pseudo-terminal");
+    let mut app = headless_app(Duration::from_secs_f64(1.0 / 60.0));
+    app.insert_resource(controller.serial_settings()).add_plugins(SerialPlugin);
+    app.world.resource_mut::<AnalogSettings>().channels.insert(
+        2,
+        ChannelCalibration { deadzone: 0.0, smoothing: 0.0, mapping: AnalogMapping::MenuSlider, ..default() },
+    );
+    app.update();
+    app.world.resource_mut::<NextState<GameState>>().set(GameState::Menu);
+
+    controller.send("ANALOG 2 1023").unwrap();
+    assert!(update_until(&mut app, |app| {
+        app.world.resource::<CountdownSettings>().seconds == CountdownSettings::MAX_SECONDS
+    }));
+}